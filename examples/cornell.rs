@@ -0,0 +1,95 @@
+use lumo::camera::CameraBuilder;
+use lumo::color::Color;
+use lumo::hittable::bvh::Bvh;
+use lumo::hittable::quad::Quad;
+use lumo::hittable::sphere::Sphere;
+use lumo::hittable::{Environment, Hittable};
+use lumo::material::{Dielectric, Diffuse, DiffuseLight, Material, Metal};
+use lumo::ray::Ray;
+use lumo::render::{Image, Renderer};
+use lumo::vector3::Vector3;
+use std::sync::Arc;
+
+fn cornell_box() -> Environment<Bvh, fn(&Ray) -> Color> {
+    let red: Arc<dyn Material> = Arc::new(Diffuse::new(Color::new(0.65, 0.05, 0.05)));
+    let white: Arc<dyn Material> = Arc::new(Diffuse::new(Color::new(0.73, 0.73, 0.73)));
+    let green: Arc<dyn Material> = Arc::new(Diffuse::new(Color::new(0.12, 0.45, 0.15)));
+    let light: Arc<dyn Material> = Arc::new(DiffuseLight::new(Color::WHITE).with_intensity(15.0));
+
+    let lamp = Arc::new(Quad::new(
+        Vector3::new(213.0, 554.0, 227.0),
+        Vector3::new(130.0, 0.0, 0.0),
+        Vector3::new(0.0, 0.0, 105.0),
+        light,
+    ));
+
+    let objects: Vec<Box<dyn Hittable>> = vec![
+        Box::new(Quad::new(
+            Vector3::new(555.0, 0.0, 0.0),
+            Vector3::new(0.0, 555.0, 0.0),
+            Vector3::new(0.0, 0.0, 555.0),
+            green,
+        )),
+        Box::new(Quad::new(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 555.0, 0.0),
+            Vector3::new(0.0, 0.0, 555.0),
+            red,
+        )),
+        Box::new(Quad::new(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(555.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 555.0),
+            white.clone(),
+        )),
+        Box::new(Quad::new(
+            Vector3::new(555.0, 555.0, 555.0),
+            Vector3::new(-555.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, -555.0),
+            white.clone(),
+        )),
+        Box::new(Quad::new(
+            Vector3::new(0.0, 0.0, 555.0),
+            Vector3::new(555.0, 0.0, 0.0),
+            Vector3::new(0.0, 555.0, 0.0),
+            white,
+        )),
+        Box::new(lamp.clone()),
+        Box::new(Sphere::new(
+            Vector3::new(190.0, 90.0, 190.0),
+            90.0,
+            Arc::new(Dielectric::new(1.5)),
+        )),
+        Box::new(Sphere::new(
+            Vector3::new(370.0, 120.0, 370.0),
+            120.0,
+            Arc::new(Metal::new(Color::new(0.8, 0.85, 0.88)).with_fuzz(0.05)),
+        )),
+    ];
+
+    let background: fn(&Ray) -> Color = |_| Color::BLACK;
+    Environment::new(Bvh::new(objects), background).with_lights(vec![lamp])
+}
+
+fn main() {
+    let image_width = 600;
+    let image_height = 600;
+    let samples = 256;
+    let depth = 16;
+
+    let camera = CameraBuilder::new()
+        .with_lookfrom(Vector3::new(278.0, 278.0, -800.0))
+        .with_lookat(Vector3::new(278.0, 278.0, 0.0))
+        .with_fov(40f64.to_radians())
+        .with_aspect_ratio(1.0)
+        .build();
+
+    let world = cornell_box();
+
+    let renderer = Renderer::new(image_width, image_height, samples, depth, camera);
+    let buffer = renderer.render(world);
+
+    if let Err(e) = Image::new(image_width, image_height, buffer).save("cornell.png") {
+        eprintln!("{:?}", e);
+    }
+}
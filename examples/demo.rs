@@ -1,22 +1,25 @@
+use lumo::background::Sky;
 use lumo::camera::CameraBuilder;
 use lumo::color::Color;
-use lumo::hittable::Environment;
+use lumo::hittable::bvh::Bvh;
 use lumo::hittable::list::HittableList;
+use lumo::hittable::plane::Plane;
 use lumo::hittable::sphere::Sphere;
+use lumo::hittable::Environment;
 use lumo::material::{Dielectric, Diffuse, Material, Metal};
-use lumo::ray::Ray;
 use lumo::render::{Image, Renderer};
+use lumo::tonemap::ToneMap;
 use lumo::vector3::Vector3;
 use rand::Rng;
 use std::sync::Arc;
 
-fn random_balls() -> Environment<HittableList, fn(&Ray) -> Color> {
+fn random_balls() -> Environment<Bvh, Sky> {
     let glass = Arc::new(Dielectric::new(1.5));
 
     let mut balls = HittableList::from_vec(vec![
-        Box::new(Sphere::new(
-            Vector3::new(0.0, -1000.0, -1.0),
-            1000.0,
+        Box::new(Plane::new(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
             Arc::new(Diffuse::new(Color::new(1.0, 0.8, 0.95))),
         )),
         Box::new(Sphere::new(
@@ -58,10 +61,9 @@ fn random_balls() -> Environment<HittableList, fn(&Ray) -> Color> {
         }
     }
 
-    Environment::new(balls, |r| {
-        let t = 0.5 * (r.direction.normalized().y + 1.0);
-        Color::WHITE.lerp(&Color::new(0.5, 0.7, 1.0), t)
-    })
+    // Late afternoon sun behind the camera's left shoulder.
+    let sun = Vector3::new(0.6, 0.45, 0.4);
+    Environment::new(Bvh::from(balls), Sky::new(sun).with_turbidity(3.0))
 }
 
 fn main() {
@@ -84,7 +86,11 @@ fn main() {
     let renderer = Renderer::new(image_width, image_height, samples, depth, camera);
     let buffer = renderer.render(world);
 
-    if let Err(e) = Image::new(image_width, image_height, buffer).save("rtiow.png") {
+    if let Err(e) = Image::new(image_width, image_height, buffer)
+        .with_exposure(-5.0)
+        .with_tone_map(ToneMap::Aces)
+        .save("rtiow.png")
+    {
         eprintln!("{:?}", e);
     }
 }
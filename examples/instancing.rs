@@ -0,0 +1,114 @@
+use lumo::camera::CameraBuilder;
+use lumo::color::Color;
+use lumo::hittable::bvh::Bvh;
+use lumo::hittable::mesh::TriangleMesh;
+use lumo::hittable::sphere::Sphere;
+use lumo::hittable::{Environment, Hittable, Instance};
+use lumo::material::{Conductor, Material, Principled};
+use lumo::ray::Ray;
+use lumo::render::{Image, Renderer};
+use lumo::transform::Transform;
+use lumo::vector3::Vector3;
+use rand::Rng;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+// An icosahedron, shared by every instance below.
+fn icosahedron(material: Arc<dyn Material>) -> TriangleMesh {
+    let p = (1.0 + 5f64.sqrt()) / 2.0;
+    let positions = vec![
+        Vector3::new(-1.0, p, 0.0),
+        Vector3::new(1.0, p, 0.0),
+        Vector3::new(-1.0, -p, 0.0),
+        Vector3::new(1.0, -p, 0.0),
+        Vector3::new(0.0, -1.0, p),
+        Vector3::new(0.0, 1.0, p),
+        Vector3::new(0.0, -1.0, -p),
+        Vector3::new(0.0, 1.0, -p),
+        Vector3::new(p, 0.0, -1.0),
+        Vector3::new(p, 0.0, 1.0),
+        Vector3::new(-p, 0.0, -1.0),
+        Vector3::new(-p, 0.0, 1.0),
+    ];
+    let indices = vec![
+        [0, 11, 5],
+        [0, 5, 1],
+        [0, 1, 7],
+        [0, 7, 10],
+        [0, 10, 11],
+        [1, 5, 9],
+        [5, 11, 4],
+        [11, 10, 2],
+        [10, 7, 6],
+        [7, 1, 8],
+        [3, 9, 4],
+        [3, 4, 2],
+        [3, 2, 6],
+        [3, 6, 8],
+        [3, 8, 9],
+        [4, 9, 5],
+        [2, 4, 11],
+        [6, 2, 10],
+        [8, 6, 7],
+        [9, 8, 1],
+    ];
+
+    let positions = positions.iter().map(|v| v.normalized()).collect();
+    TriangleMesh::new(positions, None, None, indices, material)
+}
+
+fn scene() -> Environment<Bvh, fn(&Ray) -> Color> {
+    let gold: Arc<dyn Material> = Arc::new(Conductor::gold().with_roughness(0.25));
+    let shared: Arc<dyn Hittable> = Arc::new(icosahedron(gold));
+
+    let mut rng = rand::thread_rng();
+    let mut objects: Vec<Box<dyn Hittable>> = (0..5000)
+        .map(|_| {
+            let scale = rng.gen_range(0.05..0.2);
+            let axis = Vector3::new(
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+            );
+            let position = Vector3::new(rng.gen_range(-8.0..8.0), scale, rng.gen_range(-8.0..8.0));
+
+            let transform = Transform::scale(&Vector3::new(scale, scale, scale))
+                .then(&Transform::rotate(&axis, rng.gen_range(0.0..2.0 * PI)))
+                .then(&Transform::translate(&position));
+            Box::new(Instance::new(shared.clone(), transform)) as Box<dyn Hittable>
+        })
+        .collect();
+
+    objects.push(Box::new(Sphere::new(
+        Vector3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Arc::new(Principled::new(Color::new(0.3, 0.35, 0.4)).with_roughness(0.8)),
+    )));
+
+    Environment::new(Bvh::new(objects), |r| {
+        let t = 0.5 * (r.direction.normalized().y + 1.0);
+        Color::WHITE.lerp(&Color::new(0.5, 0.7, 1.0), t)
+    })
+}
+
+fn main() {
+    let aspect_ratio = 16.0 / 9.0;
+    let image_width = 960;
+    let image_height = (image_width as f64 / aspect_ratio) as usize;
+    let samples = 64;
+    let depth = 8;
+
+    let camera = CameraBuilder::new()
+        .with_lookfrom(Vector3::new(9.0, 3.0, 9.0))
+        .with_lookat(Vector3::new(0.0, 0.0, 0.0))
+        .with_fov(35f64.to_radians())
+        .with_aspect_ratio(aspect_ratio)
+        .build();
+
+    let renderer = Renderer::new(image_width, image_height, samples, depth, camera);
+    let buffer = renderer.render(scene());
+
+    if let Err(e) = Image::new(image_width, image_height, buffer).save("instancing.png") {
+        eprintln!("{:?}", e);
+    }
+}
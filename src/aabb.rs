@@ -0,0 +1,85 @@
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+use std::ops::Range;
+
+#[derive(Debug, Clone)]
+pub struct Aabb {
+    pub min: Vector3,
+    pub max: Vector3,
+}
+
+impl Aabb {
+    pub fn new(min: Vector3, max: Vector3) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_points<'a, I: IntoIterator<Item = &'a Vector3>>(points: I) -> Option<Self> {
+        points.into_iter().fold(None, |acc, p| match acc {
+            None => Some(Self::new(p.clone(), p.clone())),
+            Some(b) => Some(Self::new(b.min.min(p), b.max.max(p))),
+        })
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(self.min.min(&other.min), self.max.max(&other.max))
+    }
+
+    // The overlap of both boxes, collapsed onto `self.min` along axes where they are disjoint.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let min = self.min.max(&other.min);
+        let max = self.max.min(&other.max).max(&min);
+        Self::new(min, max)
+    }
+
+    pub fn centroid(&self) -> Vector3 {
+        0.5 * (&self.min + &self.max)
+    }
+
+    pub fn extent(&self) -> Vector3 {
+        &self.max - &self.min
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        let d = self.extent();
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        let d = self.extent();
+        if d.x > d.y && d.x > d.z {
+            0
+        } else if d.y > d.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn hit(&self, r: &Ray, t_range: Range<f64>) -> bool {
+        self.intersect(r, t_range).is_some()
+    }
+
+    // The part of `t_range` for which the ray is inside the box.
+    pub fn intersect(&self, r: &Ray, t_range: Range<f64>) -> Option<(f64, f64)> {
+        let (mut t_min, mut t_max) = (t_range.start, t_range.end);
+
+        for axis in 0..3 {
+            let inv = 1.0 / r.direction[axis];
+            let mut t0 = (self.min[axis] - r.origin[axis]) * inv;
+            let mut t1 = (self.max[axis] - r.origin[axis]) * inv;
+
+            if inv < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            t_min = if t0 > t_min { t0 } else { t_min };
+            t_max = if t1 < t_max { t1 } else { t_max };
+
+            if t_max < t_min {
+                return None;
+            }
+        }
+
+        Some((t_min, t_max))
+    }
+}
@@ -0,0 +1,141 @@
+use super::{rotate_y, Background};
+use crate::color::Color;
+use crate::distribution::Distribution2D;
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+
+// Six square faces of incoming radiance, ordered +x, -x, +y, -y, +z, -z with the usual
+// cube map orientation: on the side faces image up is +y, and the +y face has -z at its top.
+pub struct CubeMap {
+    size: usize,
+    faces: [Vec<Color>; 6],
+    distribution: Distribution2D,
+    rotation: f64,
+    intensity: f64,
+}
+
+impl CubeMap {
+    pub fn new(size: usize, faces: [Vec<Color>; 6]) -> Self {
+        assert!(faces.iter().all(|face| face.len() == size * size));
+
+        // The faces side by side, each texel weighted by the solid angle it covers.
+        let width = 6 * size;
+        let weights: Vec<f64> = (0..width * size)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                let (face, x) = (x / size, x % size);
+                let s = 2.0 * (x as f64 + 0.5) / size as f64 - 1.0;
+                let t = 2.0 * (y as f64 + 0.5) / size as f64 - 1.0;
+                faces[face][y * size + x].luminance() / (1.0 + s * s + t * t).powf(1.5)
+            })
+            .collect();
+
+        Self {
+            distribution: Distribution2D::new(width, size, &weights),
+            size,
+            faces,
+            rotation: 0.0,
+            intensity: 1.0,
+        }
+    }
+
+    // Turns the map by `angle` radians about the y axis.
+    pub fn with_rotation(mut self, angle: f64) -> Self {
+        self.rotation = angle;
+        self
+    }
+
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    // Face and texel seen along `direction`, and the face coordinates in [-1, 1].
+    fn texel(&self, direction: &Vector3) -> (usize, usize, usize, f64, f64) {
+        let d = rotate_y(direction, -self.rotation);
+        let (ax, ay, az) = (d.x.abs(), d.y.abs(), d.z.abs());
+
+        let (face, s, t) = if ax >= ay && ax >= az {
+            if d.x > 0.0 {
+                (0, -d.z / ax, -d.y / ax)
+            } else {
+                (1, d.z / ax, -d.y / ax)
+            }
+        } else if ay >= az {
+            if d.y > 0.0 {
+                (2, d.x / ay, d.z / ay)
+            } else {
+                (3, d.x / ay, -d.z / ay)
+            }
+        } else if d.z > 0.0 {
+            (4, d.x / az, -d.y / az)
+        } else {
+            (5, -d.x / az, -d.y / az)
+        };
+
+        let n = self.size as f64;
+        let x = ((0.5 * (s + 1.0) * n) as usize).min(self.size - 1);
+        let y = ((0.5 * (t + 1.0) * n) as usize).min(self.size - 1);
+        (face, x, y, s, t)
+    }
+}
+
+fn face_direction(face: usize, s: f64, t: f64) -> Vector3 {
+    match face {
+        0 => Vector3::new(1.0, -t, -s),
+        1 => Vector3::new(-1.0, -t, s),
+        2 => Vector3::new(s, 1.0, t),
+        3 => Vector3::new(s, -1.0, -t),
+        4 => Vector3::new(s, -t, 1.0),
+        _ => Vector3::new(-s, -t, -1.0),
+    }
+}
+
+impl Background for CubeMap {
+    fn color(&self, r: &Ray) -> Color {
+        let (face, x, y, _, _) = self.texel(&r.direction);
+        self.intensity * &self.faces[face][y * self.size + x]
+    }
+
+    fn is_sampled(&self) -> bool {
+        true
+    }
+
+    fn sample_direction(&self) -> Vector3 {
+        let (xi1, xi2, xi3, xi4) = rand::random::<(f64, f64, f64, f64)>();
+        let (x, y) = self.distribution.sample(xi1, xi2);
+        let (face, x) = (x / self.size, x % self.size);
+
+        let n = self.size as f64;
+        let s = 2.0 * (x as f64 + xi3) / n - 1.0;
+        let t = 2.0 * (y as f64 + xi4) / n - 1.0;
+        rotate_y(&face_direction(face, s, t).normalized(), self.rotation)
+    }
+
+    // Texels are sampled uniformly on their face, whose area element at (s, t) subtends
+    // 1 / (1 + s² + t²)^(3/2) of solid angle.
+    fn pdf_value(&self, direction: &Vector3) -> f64 {
+        let (face, x, y, s, t) = self.texel(direction);
+        let area = (2.0 / self.size as f64).powi(2);
+        let p = self.distribution.probability(face * self.size + x, y);
+        p / area * (1.0 + s * s + t * t).powf(1.5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::background::{integrate_sphere, test_pixels};
+
+    #[test]
+    fn sampled_directions_have_a_density_integrating_to_one() {
+        let faces = std::array::from_fn(|f| test_pixels(16 + f)[f..].to_vec());
+        let map = CubeMap::new(4, faces).with_rotation(0.3);
+        for _ in 0..1000 {
+            assert!(map.pdf_value(&map.sample_direction()) > 0.0);
+        }
+
+        let integral = integrate_sphere(|d| map.pdf_value(d));
+        assert!((integral - 1.0).abs() < 1e-2, "{integral}");
+    }
+}
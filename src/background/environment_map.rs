@@ -0,0 +1,119 @@
+use super::{rotate_y, Background};
+use crate::color::Color;
+use crate::distribution::Distribution2D;
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+use std::f64::consts::PI;
+
+// An equirectangular (latitude-longitude) map of incoming radiance. The top row looks up +y and
+// the center of the image looks down -z.
+pub struct EnvironmentMap {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+    distribution: Distribution2D,
+    rotation: f64,
+    intensity: f64,
+}
+
+impl EnvironmentMap {
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        assert_eq!(pixels.len(), width * height);
+
+        // Rows near the poles cover less solid angle.
+        let weights: Vec<f64> = pixels
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let theta = PI * ((i / width) as f64 + 0.5) / height as f64;
+                c.luminance() * theta.sin()
+            })
+            .collect();
+
+        Self {
+            distribution: Distribution2D::new(width, height, &weights),
+            width,
+            height,
+            pixels,
+            rotation: 0.0,
+            intensity: 1.0,
+        }
+    }
+
+    // Turns the map by `angle` radians about the y axis.
+    pub fn with_rotation(mut self, angle: f64) -> Self {
+        self.rotation = angle;
+        self
+    }
+
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    // Pixel seen along `direction`, and the sine of its polar angle.
+    fn texel(&self, direction: &Vector3) -> (usize, usize, f64) {
+        let d = rotate_y(direction, -self.rotation).normalized();
+        let theta = d.y.clamp(-1.0, 1.0).acos();
+        let u = 0.5 + d.x.atan2(-d.z) / (2.0 * PI);
+        let v = theta / PI;
+
+        let x = ((u * self.width as f64) as usize).min(self.width - 1);
+        let y = ((v * self.height as f64) as usize).min(self.height - 1);
+        (x, y, theta.sin())
+    }
+}
+
+impl Background for EnvironmentMap {
+    fn color(&self, r: &Ray) -> Color {
+        let (x, y, _) = self.texel(&r.direction);
+        self.intensity * &self.pixels[y * self.width + x]
+    }
+
+    fn is_sampled(&self) -> bool {
+        true
+    }
+
+    fn sample_direction(&self) -> Vector3 {
+        let (xi1, xi2, xi3, xi4) = rand::random::<(f64, f64, f64, f64)>();
+        let (x, y) = self.distribution.sample(xi1, xi2);
+        let u = (x as f64 + xi3) / self.width as f64;
+        let v = (y as f64 + xi4) / self.height as f64;
+
+        let (theta, phi) = (PI * v, 2.0 * PI * (u - 0.5));
+        let d = Vector3::new(
+            theta.sin() * phi.sin(),
+            theta.cos(),
+            -theta.sin() * phi.cos(),
+        );
+        rotate_y(&d, self.rotation)
+    }
+
+    // Pixels are sampled uniformly in (u, v), which covers 2π² sin θ of solid angle per unit area.
+    fn pdf_value(&self, direction: &Vector3) -> f64 {
+        let (x, y, sin) = self.texel(direction);
+        if sin <= 0.0 {
+            return 0.0;
+        }
+
+        let pixels = (self.width * self.height) as f64;
+        self.distribution.probability(x, y) * pixels / (2.0 * PI * PI * sin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::background::{integrate_sphere, test_pixels};
+
+    #[test]
+    fn sampled_directions_have_a_density_integrating_to_one() {
+        let map = EnvironmentMap::new(8, 4, test_pixels(32)).with_rotation(0.3);
+        for _ in 0..1000 {
+            assert!(map.pdf_value(&map.sample_direction()) > 0.0);
+        }
+
+        let integral = integrate_sphere(|d| map.pdf_value(d));
+        assert!((integral - 1.0).abs() < 1e-2, "{integral}");
+    }
+}
@@ -0,0 +1,95 @@
+use crate::color::Color;
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+
+// Radiance arriving along rays that escape the scene.
+pub trait Background: Send + Sync {
+    fn color(&self, r: &Ray) -> Color;
+
+    // Backgrounds that can be importance sampled take part in light sampling; the others are
+    // only found by rays escaping after a scattering event.
+    fn is_sampled(&self) -> bool {
+        false
+    }
+
+    fn sample_direction(&self) -> Vector3 {
+        Vector3::new(1.0, 0.0, 0.0)
+    }
+
+    fn pdf_value(&self, _direction: &Vector3) -> f64 {
+        0.0
+    }
+}
+
+impl<F: Fn(&Ray) -> Color + Send + Sync> Background for F {
+    fn color(&self, r: &Ray) -> Color {
+        self(r)
+    }
+}
+
+impl Background for Box<dyn Background> {
+    fn color(&self, r: &Ray) -> Color {
+        (**self).color(r)
+    }
+
+    fn is_sampled(&self) -> bool {
+        (**self).is_sampled()
+    }
+
+    fn sample_direction(&self) -> Vector3 {
+        (**self).sample_direction()
+    }
+
+    fn pdf_value(&self, direction: &Vector3) -> f64 {
+        (**self).pdf_value(direction)
+    }
+}
+
+// Rotation by `angle` radians about the y axis.
+fn rotate_y(v: &Vector3, angle: f64) -> Vector3 {
+    let (sin, cos) = angle.sin_cos();
+    Vector3::new(cos * v.x + sin * v.z, v.y, cos * v.z - sin * v.x)
+}
+
+pub mod cube_map;
+pub mod environment_map;
+pub mod sky;
+
+pub use cube_map::*;
+pub use environment_map::*;
+pub use sky::*;
+
+// Integral of `f` over the sphere of directions by the midpoint rule on a latitude-longitude
+// grid, for checking sampling densities.
+#[cfg(test)]
+fn integrate_sphere(f: impl Fn(&Vector3) -> f64) -> f64 {
+    use std::f64::consts::PI;
+
+    let (rows, columns) = (256, 512);
+    let (d_theta, d_phi) = (PI / rows as f64, 2.0 * PI / columns as f64);
+    let mut sum = 0.0;
+    for i in 0..rows {
+        let theta = (i as f64 + 0.5) * d_theta;
+        for j in 0..columns {
+            let phi = (j as f64 + 0.5) * d_phi;
+            let d = Vector3::new(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            );
+            sum += f(&d) * theta.sin() * d_theta * d_phi;
+        }
+    }
+    sum
+}
+
+// Pixels of varying brightness, a third of them black.
+#[cfg(test)]
+fn test_pixels(count: usize) -> Vec<Color> {
+    (0..count)
+        .map(|i| match i % 3 {
+            0 => Color::BLACK,
+            _ => Color::new(i as f64, 1.0, 0.5),
+        })
+        .collect()
+}
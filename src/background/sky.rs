@@ -0,0 +1,287 @@
+use super::{Background, EnvironmentMap};
+use crate::color::{planck, spectrum_xyz, Color};
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+use std::f64::consts::{FRAC_PI_2, PI};
+use std::sync::OnceLock;
+
+// Angular radius of the sun as seen from the earth, in radians.
+const SUN_RADIUS: f64 = 0.004_654;
+
+// Luminance of the sun outside the atmosphere, in kcd/m².
+const SUN_LUMINANCE: f64 = 1.88e6;
+
+// Preetham, Shirley and Smits, "A Practical Analytic Model for Daylight" (1999): a sky dome
+// parameterized by sun direction and turbidity, plus the sun disk attenuated along its path
+// through the atmosphere. Radiance is in kcd/m², so scenes lit by it need a negative exposure
+// of around five stops. Directions below the horizon see a diffuse ground lit by sun and sky.
+pub struct Sky {
+    sun: Vector3,
+    turbidity: f64,
+    ground_albedo: Color,
+    intensity: f64,
+    sun_disk: bool,
+    model: OnceLock<Model>,
+}
+
+impl Sky {
+    pub fn new(sun_direction: Vector3) -> Self {
+        Self {
+            sun: sun_direction.normalized(),
+            turbidity: 2.5,
+            ground_albedo: Color::new(0.3, 0.3, 0.3),
+            intensity: 1.0,
+            sun_disk: true,
+            model: OnceLock::new(),
+        }
+    }
+
+    // Haziness of the atmosphere, from 2 (very clear) to 10 (hazy).
+    pub fn with_turbidity(mut self, turbidity: f64) -> Self {
+        assert!(
+            (1.7..=10.0).contains(&turbidity),
+            "turbidity must be between 1.7 and 10"
+        );
+        self.turbidity = turbidity;
+        self
+    }
+
+    pub fn with_ground_albedo(mut self, albedo: Color) -> Self {
+        self.ground_albedo = albedo;
+        self
+    }
+
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    // Leaves out the sun disk, e.g. when the sun is modeled by a separate light.
+    pub fn without_sun(mut self) -> Self {
+        self.sun_disk = false;
+        self
+    }
+
+    fn model(&self) -> &Model {
+        self.model.get_or_init(|| Model::new(self))
+    }
+
+    fn sun_visible(&self) -> bool {
+        self.sun_disk && self.sun.y > 0.0
+    }
+
+    fn sun_probability(&self) -> f64 {
+        if self.sun_visible() {
+            0.5
+        } else {
+            0.0
+        }
+    }
+}
+
+// Quantities derived from the sky's parameters on first use.
+struct Model {
+    sun: Vector3,
+    theta_s: f64,
+    zenith: [f64; 3],
+    perez: [[f64; 5]; 3],
+    sun_radiance: Option<Color>,
+    ground: Color,
+    sampler: EnvironmentMap,
+}
+
+impl Model {
+    fn new(sky: &Sky) -> Self {
+        // The model is only fitted for the sun above the horizon.
+        let theta_s = sky.sun.y.clamp(-1.0, 1.0).acos().min(FRAC_PI_2);
+        let t = sky.turbidity;
+
+        let chromaticity = |m: [[f64; 4]; 3]| {
+            let th = [theta_s.powi(3), theta_s * theta_s, theta_s, 1.0];
+            let row = |r: [f64; 4]| r.iter().zip(&th).map(|(a, b)| a * b).sum::<f64>();
+            t * t * row(m[0]) + t * row(m[1]) + row(m[2])
+        };
+
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_s);
+        let zenith = [
+            (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192,
+            chromaticity([
+                [0.00166, -0.00375, 0.00209, 0.0],
+                [-0.02903, 0.06377, -0.03202, 0.00394],
+                [0.11693, -0.21196, 0.06052, 0.25886],
+            ]),
+            chromaticity([
+                [0.00275, -0.00610, 0.00317, 0.0],
+                [-0.04214, 0.08970, -0.04153, 0.00516],
+                [0.15346, -0.26756, 0.06670, 0.26688],
+            ]),
+        ];
+
+        let perez = [
+            [
+                0.1787 * t - 1.4630,
+                -0.3554 * t + 0.4275,
+                -0.0227 * t + 5.3251,
+                0.1206 * t - 2.5771,
+                -0.0670 * t + 0.3703,
+            ],
+            [
+                -0.0193 * t - 0.2592,
+                -0.0665 * t + 0.0008,
+                -0.0004 * t + 0.2125,
+                -0.0641 * t - 0.8989,
+                -0.0033 * t + 0.0452,
+            ],
+            [
+                -0.0167 * t - 0.2608,
+                -0.0950 * t + 0.0092,
+                -0.0079 * t + 0.2102,
+                -0.0441 * t - 1.6537,
+                -0.0109 * t + 0.0529,
+            ],
+        ];
+
+        let mut model = Self {
+            sun: sky.sun.clone(),
+            theta_s,
+            zenith,
+            perez,
+            sun_radiance: sky.sun_visible().then(|| sun_radiance(theta_s, t)),
+            ground: Color::BLACK,
+            sampler: EnvironmentMap::new(1, 1, vec![Color::BLACK]),
+        };
+
+        // Horizontal illuminance from the dome by the midpoint rule, plus the sun itself.
+        const ROWS: usize = 32;
+        const COLUMNS: usize = 64;
+        let (d_theta, d_phi) = (FRAC_PI_2 / ROWS as f64, 2.0 * PI / COLUMNS as f64);
+        let mut illuminance = Color::BLACK;
+        for i in 0..ROWS {
+            let theta = (i as f64 + 0.5) * d_theta;
+            for j in 0..COLUMNS {
+                let phi = (j as f64 + 0.5) * d_phi;
+                let d = Vector3::new(
+                    theta.sin() * phi.cos(),
+                    theta.cos(),
+                    theta.sin() * phi.sin(),
+                );
+                let weight = theta.cos() * theta.sin() * d_theta * d_phi;
+                illuminance = illuminance + weight * model.dome(&d);
+            }
+        }
+        if let Some(sun) = &model.sun_radiance {
+            let solid_angle = 2.0 * PI * (1.0 - SUN_RADIUS.cos());
+            illuminance = illuminance + solid_angle * sky.sun.y * sun;
+        }
+        model.ground = &sky.ground_albedo * illuminance / PI;
+
+        // A coarse latitude-longitude table of the dome and ground guides sampling away from
+        // the sun; the disk itself is too small for it and is sampled separately.
+        let (width, height) = (64, 32);
+        let pixels = (0..width * height)
+            .map(|i| {
+                let theta = PI * ((i / width) as f64 + 0.5) / height as f64;
+                let phi = 2.0 * PI * ((i % width) as f64 + 0.5) / width as f64 - PI;
+                let d = Vector3::new(
+                    theta.sin() * phi.sin(),
+                    theta.cos(),
+                    -theta.sin() * phi.cos(),
+                );
+                model.radiance(&d, false)
+            })
+            .collect();
+        model.sampler = EnvironmentMap::new(width, height, pixels);
+
+        model
+    }
+
+    // Sky radiance for a direction above the horizon.
+    fn dome(&self, d: &Vector3) -> Color {
+        let theta_s = self.theta_s;
+        let cos_gamma = d.dot(&self.sun).clamp(-1.0, 1.0);
+        let gamma = cos_gamma.acos();
+        let cos_theta = d.y.max(1e-3);
+
+        let perez = |[a, b, c, d, e]: [f64; 5], cos_theta: f64, gamma: f64, cos_gamma: f64| {
+            (1.0 + a * (b / cos_theta).exp())
+                * (1.0 + c * (d * gamma).exp() + e * cos_gamma * cos_gamma)
+        };
+        let [big_y, x, y]: [f64; 3] = std::array::from_fn(|i| {
+            let p = self.perez[i];
+            self.zenith[i] * perez(p, cos_theta, gamma, cos_gamma)
+                / perez(p, 1.0, theta_s, theta_s.cos())
+        });
+
+        let c = Color::from_xyz(x / y * big_y, big_y, (1.0 - x - y) / y * big_y);
+        Color::new(c.r.max(0.0), c.g.max(0.0), c.b.max(0.0))
+    }
+
+    // Radiance along a unit direction.
+    fn radiance(&self, d: &Vector3, sun_disk: bool) -> Color {
+        if d.y < 0.0 {
+            return self.ground.clone();
+        }
+
+        let sky = self.dome(d);
+        match &self.sun_radiance {
+            Some(sun) if sun_disk && d.dot(&self.sun) >= SUN_RADIUS.cos() => sky + sun,
+            _ => sky,
+        }
+    }
+}
+
+// Extraterrestrial sunlight as a 5778 K blackbody, attenuated by Rayleigh and aerosol scattering
+// over the optical path of a sun at zenith angle `theta_s` (Preetham et al., appendix A.2).
+fn sun_radiance(theta_s: f64, turbidity: f64) -> Color {
+    let mass = 1.0 / (theta_s.cos() + 0.15 * (93.885 - theta_s.to_degrees()).powf(-1.253));
+    let beta = 0.04608 * turbidity - 0.04586;
+    let transmittance = |nm: f64| {
+        let um = nm * 1e-3;
+        let rayleigh = (-0.008735 * um.powf(-4.08) * mass).exp();
+        let aerosol = (-beta * um.powf(-1.3) * mass).exp();
+        rayleigh * aerosol
+    };
+
+    let (_, reference, _) = spectrum_xyz(|nm| planck(nm, 5778.0));
+    let (x, y, z) = spectrum_xyz(|nm| planck(nm, 5778.0) * transmittance(nm));
+    let c = Color::from_xyz(x, y, z) * (SUN_LUMINANCE / reference);
+    Color::new(c.r.max(0.0), c.g.max(0.0), c.b.max(0.0))
+}
+
+impl Background for Sky {
+    fn color(&self, r: &Ray) -> Color {
+        self.intensity * self.model().radiance(&r.direction.normalized(), true)
+    }
+
+    fn is_sampled(&self) -> bool {
+        true
+    }
+
+    fn sample_direction(&self) -> Vector3 {
+        if rand::random::<f64>() >= self.sun_probability() {
+            return self.model().sampler.sample_direction();
+        }
+
+        let cos_max = SUN_RADIUS.cos();
+        let (xi1, xi2) = rand::random::<(f64, f64)>();
+        let z = 1.0 + xi1 * (cos_max - 1.0);
+        let sin = (1.0 - z * z).max(0.0).sqrt();
+        let phi = 2.0 * PI * xi2;
+
+        let (u, v) = self.sun.orthonormal_basis();
+        sin * phi.cos() * u + sin * phi.sin() * v + z * &self.sun
+    }
+
+    fn pdf_value(&self, direction: &Vector3) -> f64 {
+        let p = self.sun_probability();
+        let d = direction.normalized();
+        let cos_max = SUN_RADIUS.cos();
+
+        let sun = if d.dot(&self.sun) >= cos_max {
+            1.0 / (2.0 * PI * (1.0 - cos_max))
+        } else {
+            0.0
+        };
+        p * sun + (1.0 - p) * self.model().sampler.pdf_value(&d)
+    }
+}
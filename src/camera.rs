@@ -10,6 +10,7 @@ pub struct CameraBuilder {
     aspect_ratio: f64,
     aperture: f64,
     focus_dist: f64,
+    shutter: (f64, f64),
 }
 
 impl Default for CameraBuilder {
@@ -28,6 +29,7 @@ impl CameraBuilder {
             aspect_ratio: 16.0 / 9.0,
             aperture: 0.0,
             focus_dist: 1.0,
+            shutter: (0.0, 0.0),
         }
     }
 
@@ -41,6 +43,7 @@ impl CameraBuilder {
             self.aperture,
             self.focus_dist,
         )
+        .with_shutter(self.shutter.0, self.shutter.1)
     }
 
     pub fn with_lookfrom(mut self, lookfrom: Vector3) -> Self {
@@ -77,6 +80,12 @@ impl CameraBuilder {
         self.focus_dist = focus_dist;
         self
     }
+
+    // Rays are spread uniformly over the times the shutter is open.
+    pub fn with_shutter(mut self, open: f64, close: f64) -> Self {
+        self.shutter = (open, close);
+        self
+    }
 }
 
 pub struct Camera {
@@ -87,6 +96,7 @@ pub struct Camera {
     u: Vector3,
     v: Vector3,
     lens_radius: f64,
+    shutter: (f64, f64),
 }
 
 impl Camera {
@@ -121,9 +131,15 @@ impl Camera {
             u,
             v,
             lens_radius,
+            shutter: (0.0, 0.0),
         }
     }
 
+    pub fn with_shutter(mut self, open: f64, close: f64) -> Self {
+        self.shutter = (open, close);
+        self
+    }
+
     pub fn ray(&self, u: f64, v: f64) -> Ray {
         let scale = self.lens_radius * random_in_unit_disk();
         let offset = &self.u * scale.x + &self.v * scale.y;
@@ -131,6 +147,9 @@ impl Camera {
         let direction =
             &self.lower_left_corner + u * &self.horizontal + v * &self.vertical - &origin;
 
-        Ray::new(origin, direction)
+        let (open, close) = self.shutter;
+        let time = open + rand::random::<f64>() * (close - open);
+
+        Ray::new(origin, direction).with_time(time)
     }
 }
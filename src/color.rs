@@ -1,6 +1,7 @@
 use crate::vector3::Vector3;
 use overload::overload;
 use std::ops;
+use std::sync::OnceLock;
 
 #[derive(Debug, Clone)]
 pub struct Color {
@@ -17,17 +18,97 @@ impl Color {
         Self { r, g, b }
     }
 
-    pub fn gamma(self) -> Self {
-        Self {
-            r: self.r.sqrt(),
-            g: self.g.sqrt(),
-            b: self.b.sqrt(),
-        }
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
+    pub fn linear_to_srgb(self) -> Self {
+        let f = |c: f64| {
+            if c <= 0.0031308 {
+                12.92 * c
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        };
+
+        Self::new(f(self.r), f(self.g), f(self.b))
+    }
+
+    pub fn srgb_to_linear(self) -> Self {
+        let f = |c: f64| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        Self::new(f(self.r), f(self.g), f(self.b))
     }
 
     pub fn lerp(&self, other: &Self, t: f64) -> Self {
         (1.0 - t) * self + t * other
     }
+
+    pub fn from_xyz(x: f64, y: f64, z: f64) -> Self {
+        Self::new(
+            3.2406 * x - 1.5372 * y - 0.4986 * z,
+            -0.9689 * x + 1.8758 * y + 0.0415 * z,
+            0.0557 * x - 0.2040 * y + 1.0570 * z,
+        )
+    }
+
+    // Radiance of a blackbody at `kelvin`, relative to a 6500 K blackbody of unit luminance.
+    pub fn blackbody(kelvin: f64) -> Self {
+        static REFERENCE: OnceLock<f64> = OnceLock::new();
+        let reference = *REFERENCE.get_or_init(|| planck_xyz(6500.0).1);
+
+        let (x, y, z) = planck_xyz(kelvin);
+        let c = Self::from_xyz(x, y, z) / reference;
+        Self::new(c.r.max(0.0), c.g.max(0.0), c.b.max(0.0))
+    }
+}
+
+// CIE 1931 XYZ of a spectral distribution given per nanometre over 360..830 nm, using the
+// multi-lobe colour matching function fit of Wyman et al., "Simple Analytic Approximations to
+// the CIE XYZ Color Matching Functions" (2013).
+pub(crate) fn spectrum_xyz(spectrum: impl Fn(f64) -> f64) -> (f64, f64, f64) {
+    let g = |l: f64, mu: f64, s1: f64, s2: f64| {
+        let s = if l < mu { s1 } else { s2 };
+        (-0.5 * ((l - mu) / s).powi(2)).exp()
+    };
+
+    (360..=830)
+        .step_by(5)
+        .fold((0.0, 0.0, 0.0), |(x, y, z), nm| {
+            let l = nm as f64;
+            let value = spectrum(l);
+
+            let xb = 1.056 * g(l, 599.8, 37.9, 31.0) + 0.362 * g(l, 442.0, 16.0, 26.7)
+                - 0.065 * g(l, 501.1, 20.4, 26.2);
+            let yb = 0.821 * g(l, 568.8, 46.9, 40.5) + 0.286 * g(l, 530.9, 16.3, 31.1);
+            let zb = 1.217 * g(l, 437.0, 11.8, 36.0) + 0.681 * g(l, 459.0, 26.0, 13.8);
+
+            (x + value * xb, y + value * yb, z + value * zb)
+        })
+}
+
+// Spectral radiance of a blackbody at `kelvin` by Planck's law, for a wavelength in nanometres.
+pub(crate) fn planck(nm: f64, kelvin: f64) -> f64 {
+    const H: f64 = 6.626_070_15e-34;
+    const C: f64 = 2.997_924_58e8;
+    const K: f64 = 1.380_649e-23;
+
+    if kelvin <= 0.0 {
+        return 0.0;
+    }
+
+    let m = nm * 1e-9;
+    2.0 * H * C * C / (m.powi(5) * ((H * C / (m * K * kelvin)).exp_m1()))
+}
+
+fn planck_xyz(kelvin: f64) -> (f64, f64, f64) {
+    spectrum_xyz(|nm| planck(nm, kelvin))
 }
 
 impl From<Vector3> for Color {
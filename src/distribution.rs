@@ -0,0 +1,126 @@
+// Discrete distribution proportional to non-negative weights. All-zero weights are sampled
+// uniformly instead.
+#[derive(Debug, Clone)]
+pub struct Distribution1D {
+    cdf: Vec<f64>,
+    total: f64,
+}
+
+impl Distribution1D {
+    pub fn new(weights: &[f64]) -> Self {
+        assert!(!weights.is_empty());
+
+        let mut cdf = Vec::with_capacity(weights.len());
+        let mut total = 0.0;
+        for &w in weights {
+            total += w.max(0.0);
+            cdf.push(total);
+        }
+
+        if total <= 0.0 {
+            let n = weights.len() as f64;
+            cdf = (1..=weights.len()).map(|i| i as f64 / n).collect();
+        } else {
+            cdf.iter_mut().for_each(|c| *c /= total);
+        }
+
+        Self { cdf, total }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cdf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cdf.is_empty()
+    }
+
+    // Sum of the weights the distribution was built from.
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+
+    // Picks an index for `xi` in [0, 1).
+    pub fn sample(&self, xi: f64) -> usize {
+        self.cdf
+            .partition_point(|&c| c <= xi)
+            .min(self.cdf.len() - 1)
+    }
+
+    pub fn probability(&self, i: usize) -> f64 {
+        match i {
+            0 => self.cdf[0],
+            _ => self.cdf[i] - self.cdf[i - 1],
+        }
+    }
+}
+
+// Distribution over the cells of a row-major grid: a row by the marginal distribution, then a
+// column within it.
+#[derive(Debug, Clone)]
+pub struct Distribution2D {
+    rows: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    pub fn new(width: usize, height: usize, weights: &[f64]) -> Self {
+        assert_eq!(weights.len(), width * height);
+
+        let rows: Vec<Distribution1D> = weights.chunks(width).map(Distribution1D::new).collect();
+        let totals: Vec<f64> = rows.iter().map(Distribution1D::total).collect();
+
+        Self {
+            marginal: Distribution1D::new(&totals),
+            rows,
+        }
+    }
+
+    // Returns the column and row picked for `xi1` and `xi2` in [0, 1).
+    pub fn sample(&self, xi1: f64, xi2: f64) -> (usize, usize) {
+        let y = self.marginal.sample(xi2);
+        let x = self.rows[y].sample(xi1);
+        (x, y)
+    }
+
+    pub fn probability(&self, x: usize, y: usize) -> f64 {
+        self.marginal.probability(y) * self.rows[y].probability(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_weight_bins_are_never_sampled() {
+        let weights = [0.0, 1.0, 0.0, 3.0, 0.0];
+        let distribution = Distribution1D::new(&weights);
+        let xis = (0..1000)
+            .map(|k| k as f64 / 1000.0)
+            .chain([0.25, 1.0 - 1e-12]);
+        for xi in xis {
+            let i = distribution.sample(xi);
+            assert!(weights[i] > 0.0, "xi {xi} picked empty bin {i}");
+        }
+    }
+
+    #[test]
+    fn probabilities_sum_to_one() {
+        for weights in [vec![0.0, 1.0, 0.0, 3.0, 0.0], vec![0.0; 4], vec![2.0]] {
+            let distribution = Distribution1D::new(&weights);
+            let sum: f64 = (0..weights.len())
+                .map(|i| distribution.probability(i))
+                .sum();
+            assert!((sum - 1.0).abs() < 1e-12, "{weights:?}: {sum}");
+        }
+
+        let weights: Vec<f64> = (0..12).map(|i| (i % 5) as f64).collect();
+        let distribution = Distribution2D::new(4, 3, &weights);
+        let sum: f64 = (0..3)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .map(|(x, y)| distribution.probability(x, y))
+            .sum();
+        assert!((sum - 1.0).abs() < 1e-12);
+    }
+}
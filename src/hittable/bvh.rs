@@ -0,0 +1,288 @@
+use super::*;
+use crate::aabb::Aabb;
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+use std::ops::Range;
+
+const BUCKETS: usize = 12;
+const MAX_LEAF_SIZE: usize = 4;
+const PARALLEL_THRESHOLD: usize = 4096;
+
+// Either `Hittable::hit` or `Hittable::hit_surface`.
+type HitFn = fn(&dyn Hittable, &Ray, Range<f64>) -> Option<HitRecord>;
+
+struct Primitive {
+    object: Box<dyn Hittable>,
+    bbox: Aabb,
+    centroid: Vector3,
+}
+
+enum NodeKind {
+    Leaf(Vec<Box<dyn Hittable>>),
+    Branch {
+        axis: usize,
+        left: Box<BvhNode>,
+        right: Box<BvhNode>,
+    },
+}
+
+struct BvhNode {
+    bbox: Aabb,
+    kind: NodeKind,
+}
+
+impl BvhNode {
+    fn build(primitives: Vec<Primitive>) -> Self {
+        let bbox = primitives
+            .iter()
+            .skip(1)
+            .fold(primitives[0].bbox.clone(), |acc, p| acc.union(&p.bbox));
+
+        if primitives.len() <= MAX_LEAF_SIZE {
+            return Self::leaf(bbox, primitives);
+        }
+
+        let bounds = Aabb::from_points(primitives.iter().map(|p| &p.centroid)).unwrap();
+        let axis = bounds.longest_axis();
+        let (lo, extent) = (bounds.min[axis], bounds.extent()[axis]);
+
+        if extent <= 0.0 {
+            return Self::leaf(bbox, primitives);
+        }
+
+        let bucket = |p: &Primitive| {
+            let b = (BUCKETS as f64 * (p.centroid[axis] - lo) / extent) as usize;
+            b.min(BUCKETS - 1)
+        };
+
+        let mut counts = [0usize; BUCKETS];
+        let mut boxes: [Option<Aabb>; BUCKETS] = Default::default();
+        for p in primitives.iter() {
+            let b = bucket(p);
+            counts[b] += 1;
+            boxes[b] = Some(match boxes[b].take() {
+                Some(acc) => acc.union(&p.bbox),
+                None => p.bbox.clone(),
+            });
+        }
+
+        let split = (0..BUCKETS - 1)
+            .filter_map(|i| {
+                let side = |range: Range<usize>| {
+                    let count: usize = counts[range.clone()].iter().sum();
+                    let area = boxes[range]
+                        .iter()
+                        .flatten()
+                        .fold(None, |acc: Option<Aabb>, b| {
+                            Some(acc.map_or(b.clone(), |acc| acc.union(b)))
+                        })
+                        .map_or(0.0, |b| b.surface_area());
+                    (count, area)
+                };
+
+                let (n_left, a_left) = side(0..i + 1);
+                let (n_right, a_right) = side(i + 1..BUCKETS);
+
+                if n_left == 0 || n_right == 0 {
+                    return None;
+                }
+
+                let cost =
+                    (n_left as f64 * a_left + n_right as f64 * a_right) / bbox.surface_area();
+                Some((i, cost))
+            })
+            .min_by(|x, y| x.1.total_cmp(&y.1))
+            .map(|(i, _)| i)
+            .unwrap();
+
+        let parallel = primitives.len() >= PARALLEL_THRESHOLD;
+        let (left, right): (Vec<_>, Vec<_>) =
+            primitives.into_iter().partition(|p| bucket(p) <= split);
+
+        let (left, right) = if parallel {
+            rayon::join(|| Self::build(left), || Self::build(right))
+        } else {
+            (Self::build(left), Self::build(right))
+        };
+
+        Self {
+            bbox,
+            kind: NodeKind::Branch {
+                axis,
+                left: Box::new(left),
+                right: Box::new(right),
+            },
+        }
+    }
+
+    fn pdf_sum(&self, r: &Ray) -> f64 {
+        if !self.bbox.hit(r, 1e-6..f64::INFINITY) {
+            return 0.0;
+        }
+
+        match &self.kind {
+            NodeKind::Leaf(objects) => objects
+                .iter()
+                .map(|object| object.pdf_value(&r.origin, &r.direction))
+                .sum(),
+            NodeKind::Branch { left, right, .. } => left.pdf_sum(r) + right.pdf_sum(r),
+        }
+    }
+
+    fn leaf(bbox: Aabb, primitives: Vec<Primitive>) -> Self {
+        let objects = primitives.into_iter().map(|p| p.object).collect();
+        Self {
+            bbox,
+            kind: NodeKind::Leaf(objects),
+        }
+    }
+
+    fn transmittance(&self, r: &Ray, t_range: Range<f64>) -> f64 {
+        if !self.bbox.hit(r, t_range.clone()) {
+            return 1.0;
+        }
+
+        match &self.kind {
+            NodeKind::Leaf(objects) => objects
+                .iter()
+                .map(|object| object.transmittance(r, t_range.clone()))
+                .product(),
+            NodeKind::Branch { left, right, .. } => {
+                left.transmittance(r, t_range.clone()) * right.transmittance(r, t_range)
+            }
+        }
+    }
+
+    fn hit(&self, r: &Ray, t_range: Range<f64>, hit_fn: HitFn) -> Option<HitRecord> {
+        if !self.bbox.hit(r, t_range.clone()) {
+            return None;
+        }
+
+        match &self.kind {
+            NodeKind::Leaf(objects) => closest_hit(objects, r, t_range, hit_fn),
+            NodeKind::Branch { axis, left, right } => {
+                let (first, second) = if r.direction[*axis] < 0.0 {
+                    (right, left)
+                } else {
+                    (left, right)
+                };
+
+                let hit = first.hit(r, t_range.clone(), hit_fn);
+                let end = hit.as_ref().map_or(t_range.end, |h| h.t);
+                second.hit(r, t_range.start..end, hit_fn).or(hit)
+            }
+        }
+    }
+}
+
+fn closest_hit(
+    objects: &[Box<dyn Hittable>],
+    r: &Ray,
+    t_range: Range<f64>,
+    hit_fn: HitFn,
+) -> Option<HitRecord> {
+    objects.iter().fold(None, |closest, object| {
+        let end = closest.as_ref().map_or(t_range.end, |h: &HitRecord| h.t);
+        hit_fn(object.as_ref(), r, t_range.start..end).or(closest)
+    })
+}
+
+pub struct Bvh {
+    root: Option<BvhNode>,
+    unbounded: Vec<Box<dyn Hittable>>,
+}
+
+impl Bvh {
+    pub fn new(objects: Vec<Box<dyn Hittable>>) -> Self {
+        let mut primitives = Vec::with_capacity(objects.len());
+        let mut unbounded = Vec::new();
+
+        for object in objects {
+            match object.bounding_box() {
+                Some(bbox) => primitives.push(Primitive {
+                    centroid: bbox.centroid(),
+                    bbox,
+                    object,
+                }),
+                None => unbounded.push(object),
+            }
+        }
+
+        let root = if primitives.is_empty() {
+            None
+        } else {
+            Some(BvhNode::build(primitives))
+        };
+
+        Self { root, unbounded }
+    }
+
+    // Sum of the objects' direction densities, visiting only the nodes the direction passes
+    // through.
+    pub(crate) fn pdf_sum(&self, origin: &Vector3, direction: &Vector3) -> f64 {
+        let r = Ray::new(origin.clone(), direction.clone());
+        let bounded = self.root.as_ref().map_or(0.0, |root| root.pdf_sum(&r));
+
+        bounded
+            + self
+                .unbounded
+                .iter()
+                .map(|object| object.pdf_value(origin, direction))
+                .sum::<f64>()
+    }
+
+    fn closest(&self, r: &Ray, t_range: Range<f64>, hit_fn: HitFn) -> Option<HitRecord> {
+        let hit = self
+            .root
+            .as_ref()
+            .and_then(|root| root.hit(r, t_range.clone(), hit_fn));
+        let end = hit.as_ref().map_or(t_range.end, |h| h.t);
+        closest_hit(&self.unbounded, r, t_range.start..end, hit_fn).or(hit)
+    }
+}
+
+impl Hittable for Bvh {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        self.closest(r, t_range, |object, r, t_range| object.hit(r, t_range))
+    }
+
+    fn hit_surface(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        self.closest(r, t_range, |object, r, t_range| {
+            object.hit_surface(r, t_range)
+        })
+    }
+
+    fn transmittance(&self, r: &Ray, t_range: Range<f64>) -> f64 {
+        let bounded = self
+            .root
+            .as_ref()
+            .map_or(1.0, |root| root.transmittance(r, t_range.clone()));
+
+        bounded
+            * self
+                .unbounded
+                .iter()
+                .map(|object| object.transmittance(r, t_range.clone()))
+                .product::<f64>()
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        if self.unbounded.is_empty() {
+            self.root.as_ref().map(|root| root.bbox.clone())
+        } else {
+            None
+        }
+    }
+}
+
+impl From<HittableList> for Bvh {
+    fn from(list: HittableList) -> Self {
+        Self::new(list.into_vec())
+    }
+}
+
+impl FromIterator<Box<dyn Hittable>> for Bvh {
+    fn from_iter<T: IntoIterator<Item = Box<dyn Hittable>>>(iter: T) -> Self {
+        Self::new(Vec::from_iter(iter))
+    }
+}
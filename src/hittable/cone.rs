@@ -0,0 +1,89 @@
+use super::disk::{azimuth, disk_bbox, hit_cap};
+use super::*;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::transform::Transform;
+use crate::vector3::Vector3;
+use std::ops::Range;
+use std::sync::Arc;
+
+// A cone with a capped base of `radius` around `base`, narrowing to `apex`. In local space
+// the base lies on the xz plane and the apex on the y axis.
+pub struct Cone {
+    base: Vector3,
+    apex: Vector3,
+    radius: f64,
+    height: f64,
+    transform: Transform,
+    to_local: Transform,
+    material: Arc<dyn Material>,
+}
+
+impl Cone {
+    pub fn new(base: Vector3, apex: Vector3, radius: f64, material: Arc<dyn Material>) -> Self {
+        let axis = &apex - &base;
+        let transform = Transform::orient(&base, &axis);
+        Self {
+            height: axis.norm(),
+            to_local: transform.inverse(),
+            transform,
+            base,
+            apex,
+            radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let local = self.to_local.ray(r);
+        let (o, d) = (&local.origin, &local.direction);
+
+        let mut closest: Option<(f64, Vector3, (f64, f64))> = None;
+        let mut t_max = t_range.end;
+
+        // x² + z² = (R - k y)², with the radius shrinking by k per unit height.
+        let k = self.radius / self.height;
+        let rho = self.radius - k * o.y;
+        let a = d.x * d.x + d.z * d.z - k * k * d.y * d.y;
+        let b = o.x * d.x + o.z * d.z + rho * k * d.y;
+        let c = o.x * o.x + o.z * o.z - rho * rho;
+        let disc = b * b - a * c;
+        if a != 0.0 && disc >= 0.0 {
+            let (t0, t1) = ((-b - disc.sqrt()) / a, (-b + disc.sqrt()) / a);
+            for t in [t0.min(t1), t0.max(t1)] {
+                let p = local.at(t);
+                if (t_range.start..t_max).contains(&t) && (0.0..=self.height).contains(&p.y) {
+                    let n = Vector3::new(p.x, k * (self.radius - k * p.y), p.z);
+                    let n = if n.is_nearly_zero() {
+                        Vector3::new(0.0, 1.0, 0.0)
+                    } else {
+                        n.normalized()
+                    };
+                    closest = Some((t, n, (azimuth(p.x, p.z), p.y / self.height)));
+                    t_max = t;
+                    break;
+                }
+            }
+        }
+
+        if let Some((t, p)) = hit_cap(&local, 0.0, self.radius, t_range.start..t_max) {
+            let uv = (azimuth(p.x, p.z), p.x.hypot(p.z) / self.radius);
+            closest = Some((t, Vector3::new(0.0, -1.0, 0.0), uv));
+        }
+
+        let (t, n, (u, v)) = closest?;
+        Some(
+            HitRecord::new(r.at(t), self.transform.vector(&n), t, self.material.clone())
+                .with_uv(u, v)
+                .set_face(r),
+        )
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let base = disk_bbox(&self.base, &(&self.apex - &self.base), self.radius);
+        Some(base.union(&Aabb::new(self.apex.clone(), self.apex.clone())))
+    }
+}
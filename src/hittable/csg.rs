@@ -0,0 +1,111 @@
+use super::*;
+use crate::aabb::Aabb;
+use crate::ray::Ray;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl CsgOperation {
+    fn contains(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            Self::Union => in_a || in_b,
+            Self::Intersection => in_a && in_b,
+            Self::Difference => in_a && !in_b,
+        }
+    }
+}
+
+// Combines two closed objects into the solid where `operation` holds. Surfaces keep the
+// material of the operand they come from.
+pub struct Csg<A: Hittable, B: Hittable> {
+    operation: CsgOperation,
+    a: A,
+    b: B,
+}
+
+impl<A: Hittable, B: Hittable> Csg<A, B> {
+    pub fn new(operation: CsgOperation, a: A, b: B) -> Self {
+        Self { operation, a, b }
+    }
+
+    pub fn union(a: A, b: B) -> Self {
+        Self::new(CsgOperation::Union, a, b)
+    }
+
+    pub fn intersection(a: A, b: B) -> Self {
+        Self::new(CsgOperation::Intersection, a, b)
+    }
+
+    pub fn difference(a: A, b: B) -> Self {
+        Self::new(CsgOperation::Difference, a, b)
+    }
+}
+
+impl<A: Hittable, B: Hittable> Hittable for Csg<A, B> {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        self.crossings(r, t_range).into_iter().next()
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let (a, b) = (self.a.bounding_box(), self.b.bounding_box());
+        match self.operation {
+            CsgOperation::Union => Some(a?.union(&b?)),
+            CsgOperation::Intersection => match (a, b) {
+                (Some(a), Some(b)) => Some(a.intersection(&b)),
+                (a, b) => a.or(b),
+            },
+            CsgOperation::Difference => a,
+        }
+    }
+
+    // Walks the crossings of both operands in order, reporting those where the ray enters or
+    // leaves the combined solid. Whether the ray starts inside an operand is told by its
+    // first crossing, so these are searched past the end of `t_range`.
+    fn crossings(&self, r: &Ray, t_range: Range<f64>) -> Vec<HitRecord> {
+        let a = self.a.crossings(r, t_range.start..f64::INFINITY);
+        let b = self.b.crossings(r, t_range.start..f64::INFINITY);
+
+        let mut in_a = a.first().is_some_and(|hit| !hit.front_face);
+        let mut in_b = b.first().is_some_and(|hit| !hit.front_face);
+        let mut inside = self.operation.contains(in_a, in_b);
+
+        let mut a = a.into_iter().peekable();
+        let mut b = b.into_iter().peekable();
+        let mut crossings = Vec::new();
+
+        loop {
+            let from_a = match (a.peek(), b.peek()) {
+                (Some(x), Some(y)) => x.t <= y.t,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            let mut hit = if from_a { a.next() } else { b.next() }.unwrap();
+            if hit.t >= t_range.end {
+                break;
+            }
+
+            if from_a {
+                in_a = hit.front_face;
+            } else {
+                in_b = hit.front_face;
+            }
+
+            let now_inside = self.operation.contains(in_a, in_b);
+            if now_inside != inside {
+                inside = now_inside;
+                // The normal already faces the ray; only the side of the solid may change,
+                // as for surfaces subtracted by a difference.
+                hit.front_face = inside;
+                crossings.push(hit);
+            }
+        }
+
+        crossings
+    }
+}
@@ -0,0 +1,103 @@
+use super::*;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::transform::Transform;
+use crate::vector3::Vector3;
+use std::ops::Range;
+use std::sync::Arc;
+
+// A box with opposite corners `a` and `b`, optionally rotated about its center.
+pub struct Cuboid {
+    half: Vector3,
+    transform: Transform,
+    to_local: Transform,
+    material: Arc<dyn Material>,
+}
+
+impl Cuboid {
+    pub fn new(a: Vector3, b: Vector3, material: Arc<dyn Material>) -> Self {
+        let (min, max) = (a.min(&b), a.max(&b));
+        let transform = Transform::translate(&(0.5 * (&min + &max)));
+        Self {
+            half: 0.5 * (max - min),
+            to_local: transform.inverse(),
+            transform,
+            material,
+        }
+    }
+
+    pub fn with_rotation(mut self, axis: &Vector3, angle: f64) -> Self {
+        self.transform = Transform::rotate(axis, angle).then(&self.transform);
+        self.to_local = self.transform.inverse();
+        self
+    }
+}
+
+impl Cuboid {
+    // Slab test in local space, returning where the ray enters and leaves the box along with
+    // the axis of the face crossed.
+    fn slabs(&self, local: &Ray) -> Option<[(f64, usize); 2]> {
+        let (mut t_in, mut t_out) = (f64::NEG_INFINITY, f64::INFINITY);
+        let (mut axis_in, mut axis_out) = (0, 0);
+        for axis in 0..3 {
+            let inv = 1.0 / local.direction[axis];
+            let t0 = (-self.half[axis] - local.origin[axis]) * inv;
+            let t1 = (self.half[axis] - local.origin[axis]) * inv;
+            let (t0, t1) = if inv < 0.0 { (t1, t0) } else { (t0, t1) };
+
+            if t0 > t_in {
+                (t_in, axis_in) = (t0, axis);
+            }
+            if t1 < t_out {
+                (t_out, axis_out) = (t1, axis);
+            }
+        }
+
+        (t_in <= t_out).then_some([(t_in, axis_in), (t_out, axis_out)])
+    }
+
+    fn record(&self, r: &Ray, local: &Ray, t: f64, axis: usize) -> HitRecord {
+        let p = local.at(t);
+        let mut n = [0.0; 3];
+        n[axis] = p[axis].signum();
+        let n = Vector3::new(n[0], n[1], n[2]);
+
+        // Each face is mapped to the unit square by its two other coordinates.
+        let (i, j) = ((axis + 1) % 3, (axis + 2) % 3);
+        let u = 0.5 * (p[i] / self.half[i] + 1.0);
+        let v = 0.5 * (p[j] / self.half[j] + 1.0);
+
+        HitRecord::new(r.at(t), self.transform.vector(&n), t, self.material.clone())
+            .with_uv(u, v)
+            .set_face(r)
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let local = self.to_local.ray(r);
+        let (t, axis) = self
+            .slabs(&local)?
+            .into_iter()
+            .find(|(t, _)| t_range.contains(t))?;
+
+        Some(self.record(r, &local, t, axis))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let local = Aabb::new(-&self.half, self.half.clone());
+        Some(self.transform.bbox(&local))
+    }
+
+    fn crossings(&self, r: &Ray, t_range: Range<f64>) -> Vec<HitRecord> {
+        let local = self.to_local.ray(r);
+        let slabs = self.slabs(&local);
+        slabs
+            .into_iter()
+            .flatten()
+            .filter(|(t, _)| t_range.contains(t))
+            .map(|(t, axis)| self.record(r, &local, t, axis))
+            .collect()
+    }
+}
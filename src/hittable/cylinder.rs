@@ -0,0 +1,85 @@
+use super::disk::{azimuth, disk_bbox, hit_cap};
+use super::*;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::transform::Transform;
+use crate::vector3::Vector3;
+use std::ops::Range;
+use std::sync::Arc;
+
+// A capped cylinder around the segment from `base` to `top`. In local space it stands on the
+// xz plane along the y axis.
+pub struct Cylinder {
+    base: Vector3,
+    top: Vector3,
+    radius: f64,
+    height: f64,
+    transform: Transform,
+    to_local: Transform,
+    material: Arc<dyn Material>,
+}
+
+impl Cylinder {
+    pub fn new(base: Vector3, top: Vector3, radius: f64, material: Arc<dyn Material>) -> Self {
+        let axis = &top - &base;
+        let transform = Transform::orient(&base, &axis);
+        Self {
+            height: axis.norm(),
+            to_local: transform.inverse(),
+            transform,
+            base,
+            top,
+            radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let local = self.to_local.ray(r);
+        let (o, d) = (&local.origin, &local.direction);
+
+        let mut closest: Option<(f64, Vector3, (f64, f64))> = None;
+        let mut t_max = t_range.end;
+
+        let a = d.x * d.x + d.z * d.z;
+        let b = o.x * d.x + o.z * d.z;
+        let c = o.x * o.x + o.z * o.z - self.radius * self.radius;
+        let disc = b * b - a * c;
+        if a > 0.0 && disc >= 0.0 {
+            for t in [(-b - disc.sqrt()) / a, (-b + disc.sqrt()) / a] {
+                let p = local.at(t);
+                if (t_range.start..t_max).contains(&t) && (0.0..=self.height).contains(&p.y) {
+                    let n = Vector3::new(p.x, 0.0, p.z) / self.radius;
+                    closest = Some((t, n, (azimuth(p.x, p.z), p.y / self.height)));
+                    t_max = t;
+                    break;
+                }
+            }
+        }
+
+        for (y, ny) in [(0.0, -1.0), (self.height, 1.0)] {
+            if let Some((t, p)) = hit_cap(&local, y, self.radius, t_range.start..t_max) {
+                let uv = (azimuth(p.x, p.z), p.x.hypot(p.z) / self.radius);
+                closest = Some((t, Vector3::new(0.0, ny, 0.0), uv));
+                t_max = t;
+            }
+        }
+
+        let (t, n, (u, v)) = closest?;
+        Some(
+            HitRecord::new(r.at(t), self.transform.vector(&n), t, self.material.clone())
+                .with_uv(u, v)
+                .set_face(r),
+        )
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let axis = &self.top - &self.base;
+        let base = disk_bbox(&self.base, &axis, self.radius);
+        let top = disk_bbox(&self.top, &axis, self.radius);
+        Some(base.union(&top))
+    }
+}
@@ -0,0 +1,95 @@
+use super::*;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::transform::Transform;
+use crate::vector3::Vector3;
+use std::f64::consts::PI;
+use std::ops::Range;
+use std::sync::Arc;
+
+pub struct Disk {
+    center: Vector3,
+    normal: Vector3,
+    radius: f64,
+    to_local: Transform,
+    material: Arc<dyn Material>,
+}
+
+impl Disk {
+    pub fn new(center: Vector3, normal: Vector3, radius: f64, material: Arc<dyn Material>) -> Self {
+        let normal = normal.normalized();
+        Self {
+            to_local: Transform::orient(&center, &normal).inverse(),
+            center,
+            normal,
+            radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let local = self.to_local.ray(r);
+        let (t, p) = hit_cap(&local, 0.0, self.radius, t_range)?;
+
+        Some(
+            HitRecord::new(r.at(t), self.normal.clone(), t, self.material.clone())
+                .with_uv(azimuth(p.x, p.z), p.x.hypot(p.z) / self.radius)
+                .set_face(r),
+        )
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(disk_bbox(&self.center, &self.normal, self.radius))
+    }
+
+    fn is_sampled(&self) -> bool {
+        true
+    }
+
+    fn pdf_value(&self, origin: &Vector3, direction: &Vector3) -> f64 {
+        let r = Ray::new(origin.clone(), direction.normalized());
+        match self.hit(&r, 1e-6..f64::INFINITY) {
+            Some(hit) => {
+                let cos = self.normal.dot(&r.direction).abs();
+                let area = PI * self.radius * self.radius;
+                hit.t * hit.t / (cos * area)
+            }
+            None => 0.0,
+        }
+    }
+
+    fn sample_direction(&self, origin: &Vector3) -> Vector3 {
+        let (xi1, xi2) = rand::random::<(f64, f64)>();
+        let (rho, phi) = (self.radius * xi1.sqrt(), 2.0 * PI * xi2);
+        let local = Vector3::new(rho * phi.cos(), 0.0, rho * phi.sin());
+        self.to_local.inverse().point(&local) - origin
+    }
+}
+
+// Hit of a ray in local space with the disk of `radius` around the y axis at height `y`.
+pub(super) fn hit_cap(r: &Ray, y: f64, radius: f64, t_range: Range<f64>) -> Option<(f64, Vector3)> {
+    let t = (y - r.origin.y) / r.direction.y;
+    if !t_range.contains(&t) {
+        return None;
+    }
+
+    let p = r.at(t);
+    (p.x * p.x + p.z * p.z <= radius * radius).then_some((t, p))
+}
+
+// Angle around the local y axis mapped to [0, 1], as for spheres.
+pub(super) fn azimuth(x: f64, z: f64) -> f64 {
+    ((-z).atan2(x) + PI) / (2.0 * PI)
+}
+
+// Tight bounds of a disk: along each axis it reaches `radius` times the sine of the angle
+// between that axis and the normal.
+pub(super) fn disk_bbox(center: &Vector3, normal: &Vector3, radius: f64) -> Aabb {
+    let n = normal.normalized();
+    let e = |a: f64| radius * (1.0 - a * a).max(0.0).sqrt();
+    let e = Vector3::new(e(n.x), e(n.y), e(n.z));
+    Aabb::new(center - &e, center + &e)
+}
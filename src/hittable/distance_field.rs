@@ -0,0 +1,117 @@
+use super::*;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::sdf::Sdf;
+use crate::vector3::Vector3;
+use std::ops::Range;
+use std::sync::Arc;
+
+// Sphere-traces the surface of a signed distance function.
+pub struct DistanceField<S: Sdf> {
+    sdf: S,
+    bounds: Option<Aabb>,
+    material: Arc<dyn Material>,
+    epsilon: f64,
+    max_steps: usize,
+    step_scale: f64,
+}
+
+impl<S: Sdf> DistanceField<S> {
+    pub fn new(sdf: S, material: Arc<dyn Material>) -> Self {
+        Self {
+            bounds: sdf.bounding_box(),
+            sdf,
+            material,
+            epsilon: 1e-4,
+            max_steps: 512,
+            step_scale: 1.0,
+        }
+    }
+
+    // Needed when the function cannot bound itself, e.g. for repetitions or closures.
+    pub fn with_bounds(mut self, bounds: Aabb) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    // Fraction of the distance bound taken per step, for functions overestimating distance.
+    pub fn with_step_scale(mut self, step_scale: f64) -> Self {
+        self.step_scale = step_scale;
+        self
+    }
+
+    // Central differences over a tetrahedron; points outwards.
+    fn gradient(&self, p: &Vector3) -> Vector3 {
+        let h = self.epsilon;
+        [
+            (1.0, -1.0, -1.0),
+            (-1.0, -1.0, 1.0),
+            (-1.0, 1.0, -1.0),
+            (1.0, 1.0, 1.0),
+        ]
+        .into_iter()
+        .map(|(x, y, z)| {
+            let k = Vector3::new(x, y, z);
+            self.sdf.distance(&(p + h * &k)) * k
+        })
+        .fold(Vector3::ZERO, |acc, v| acc + v)
+    }
+}
+
+impl<S: Sdf> Hittable for DistanceField<S> {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let (mut t, t_end) = match &self.bounds {
+            Some(bounds) => bounds.intersect(r, t_range.clone())?,
+            None => (t_range.start, t_range.end),
+        };
+        let speed = r.direction.norm();
+
+        // The side of the surface the ray travels on, +1 outside and -1 inside. A ray starting
+        // on the surface, as scattered rays do, has to move clear of it before it can hit.
+        let mut side = None;
+        let mut clear = true;
+        for _ in 0..self.max_steps {
+            if t > t_end {
+                return None;
+            }
+
+            let p = r.at(t);
+            let d = self.sdf.distance(&p);
+            let side = match side {
+                Some(side) => side,
+                None if d.abs() < self.epsilon && t <= t_range.start => {
+                    clear = false;
+                    let outwards = self.gradient(&p).dot(&r.direction) > 0.0;
+                    *side.insert(if outwards { 1.0 } else { -1.0 })
+                }
+                None => *side.insert(if d < 0.0 { -1.0 } else { 1.0 }),
+            };
+
+            if !clear {
+                clear = side * d >= self.epsilon;
+            } else if side * d < self.epsilon {
+                let n = self.gradient(&p).normalized();
+                return Some(HitRecord::new(p, n, t, self.material.clone()).set_face(r));
+            }
+
+            t += self.step_scale * d.abs().max(self.epsilon) / speed;
+        }
+
+        None
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.bounds.clone()
+    }
+}
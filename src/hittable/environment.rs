@@ -1,33 +1,161 @@
-use super::{HitRecord, Hittable};
+use super::{Atmosphere, HitRecord, Hittable};
+use crate::aabb::Aabb;
+use crate::background::Background;
 use crate::color::Color;
+use crate::light::Light;
 use crate::ray::Ray;
+use crate::vector3::Vector3;
+use rand::Rng;
 use std::ops::Range;
+use std::sync::Arc;
 
-pub struct Environment<H: Hittable, B: Fn(&Ray) -> Color + Send + Sync> {
+pub struct Environment<H: Hittable, B: Background> {
     world: H,
     bg: B,
+    lights: Vec<Arc<dyn Hittable>>,
+    punctual_lights: Vec<Arc<dyn Light>>,
+    atmosphere: Option<Atmosphere>,
 }
 
 impl<H, B> Environment<H, B>
 where
     H: Hittable,
-    B: Fn(&Ray) -> Color + Send + Sync,
+    B: Background,
 {
     pub fn new(world: H, bg: B) -> Self {
-        Self { world, bg }
+        Self {
+            world,
+            bg,
+            lights: Vec::new(),
+            punctual_lights: Vec::new(),
+            atmosphere: None,
+        }
+    }
+
+    // Lights that can't be sampled would only dilute the others, so they are left to be found
+    // by scattered rays.
+    pub fn with_lights(mut self, lights: Vec<Arc<dyn Hittable>>) -> Self {
+        self.lights = lights
+            .into_iter()
+            .filter(|light| light.is_sampled())
+            .collect();
+        self
+    }
+
+    pub fn with_punctual_lights(mut self, lights: Vec<Arc<dyn Light>>) -> Self {
+        self.punctual_lights = lights;
+        self
+    }
+
+    pub fn with_atmosphere(mut self, atmosphere: Atmosphere) -> Self {
+        self.atmosphere = Some(atmosphere);
+        self
     }
 
     pub fn background(&self, r: &Ray) -> Color {
-        (self.bg)(r)
+        self.bg.color(r)
+    }
+
+    pub fn punctual_lights(&self) -> &[Arc<dyn Light>] {
+        &self.punctual_lights
+    }
+
+    // A background that can be sampled counts as one more light.
+    fn light_count(&self) -> usize {
+        self.lights.len() + self.bg.is_sampled() as usize
+    }
+
+    pub fn has_lights(&self) -> bool {
+        self.light_count() > 0
+    }
+
+    pub fn sample_light(&self, origin: &Vector3) -> Vector3 {
+        let i = rand::thread_rng().gen_range(0..self.light_count());
+        match self.lights.get(i) {
+            Some(light) => light.sample_direction(origin),
+            None => self.bg.sample_direction(),
+        }
+    }
+
+    pub fn light_pdf(&self, origin: &Vector3, direction: &Vector3) -> f64 {
+        if !self.has_lights() {
+            return 0.0;
+        }
+
+        let sum: f64 = self
+            .lights
+            .iter()
+            .map(|light| light.pdf_value(origin, direction))
+            .sum();
+        let bg = if self.bg.is_sampled() {
+            self.bg.pdf_value(direction)
+        } else {
+            0.0
+        };
+        (sum + bg) / self.light_count() as f64
     }
 }
 
 impl<H, B> Hittable for Environment<H, B>
 where
     H: Hittable,
-    B: Fn(&Ray) -> Color + Send + Sync,
+    B: Background,
 {
     fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
-        self.world.hit(r, t_range)
+        match &self.atmosphere {
+            Some(atmosphere) => {
+                let hit = self.world.hit(r, t_range.clone());
+                atmosphere.scatter(r, t_range, hit)
+            }
+            None => self.world.hit(r, t_range),
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.world.bounding_box()
+    }
+
+    fn hit_surface(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        self.world.hit_surface(r, t_range)
+    }
+
+    fn transmittance(&self, r: &Ray, t_range: Range<f64>) -> f64 {
+        let atmosphere = self.atmosphere.as_ref().map_or(1.0, |atmosphere| {
+            atmosphere.transmittance(r, t_range.clone())
+        });
+
+        atmosphere * self.world.transmittance(r, t_range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::{Cylinder, HittableList, Sphere};
+    use crate::material::DiffuseLight;
+
+    #[test]
+    fn lights_that_cannot_be_sampled_are_skipped() {
+        let material = Arc::new(DiffuseLight::new(Color::new(1.0, 1.0, 1.0)));
+        let lamp = Arc::new(Sphere::new(
+            Vector3::new(0.0, 2.0, 0.0),
+            0.5,
+            material.clone(),
+        ));
+        let tube = Arc::new(Cylinder::new(
+            Vector3::new(-1.0, -2.0, 0.0),
+            Vector3::new(1.0, -2.0, 0.0),
+            0.1,
+            material,
+        ));
+        let env = Environment::new(HittableList::new(), |_: &Ray| Color::BLACK)
+            .with_lights(vec![lamp, tube]);
+
+        let origin = Vector3::new(0.0, 0.0, 0.0);
+        for _ in 0..100 {
+            let direction = env.sample_light(&origin);
+            assert!(direction.y > 0.0);
+            assert!(env.light_pdf(&origin, &direction) > 0.0);
+        }
     }
 }
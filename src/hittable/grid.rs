@@ -0,0 +1,200 @@
+use super::medium::scatter_record;
+use super::*;
+use crate::aabb::Aabb;
+use crate::color::Color;
+use crate::material::{BsdfSample, Material};
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+use std::ops::Range;
+use std::sync::Arc;
+
+// A dense scalar field over `bounds`, stored x-fastest and sampled at cell centres.
+pub struct VoxelGrid {
+    resolution: [usize; 3],
+    bounds: Aabb,
+    data: Vec<f32>,
+    max: f64,
+}
+
+impl VoxelGrid {
+    pub fn new(resolution: [usize; 3], bounds: Aabb, data: Vec<f32>) -> Self {
+        assert_eq!(data.len(), resolution.iter().product::<usize>());
+        assert!(resolution.iter().all(|&n| n > 0));
+
+        let max = data.iter().fold(0.0f64, |acc, &v| acc.max(v as f64));
+        Self {
+            resolution,
+            bounds,
+            data,
+            max,
+        }
+    }
+
+    pub fn bounds(&self) -> &Aabb {
+        &self.bounds
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    // Trilinearly interpolated value at `p`; zero outside the bounds.
+    pub fn value(&self, p: &Vector3) -> f64 {
+        let extent = self.bounds.extent();
+        let mut base = [0usize; 3];
+        let mut frac = [0.0; 3];
+
+        for axis in 0..3 {
+            let x = (p[axis] - self.bounds.min[axis]) / extent[axis];
+            if !(0.0..=1.0).contains(&x) {
+                return 0.0;
+            }
+
+            let n = self.resolution[axis];
+            let g = (x * n as f64 - 0.5).clamp(0.0, (n - 1) as f64);
+            base[axis] = (g as usize).min(n.saturating_sub(2));
+            frac[axis] = if n > 1 { g - base[axis] as f64 } else { 0.0 };
+        }
+
+        let [nx, ny, nz] = self.resolution;
+        let at = |dx: usize, dy: usize, dz: usize| {
+            let x = (base[0] + dx).min(nx - 1);
+            let y = (base[1] + dy).min(ny - 1);
+            let z = (base[2] + dz).min(nz - 1);
+            self.data[x + nx * (y + ny * z)] as f64
+        };
+
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+        let [fx, fy, fz] = frac;
+        let c00 = lerp(at(0, 0, 0), at(1, 0, 0), fx);
+        let c10 = lerp(at(0, 1, 0), at(1, 1, 0), fx);
+        let c01 = lerp(at(0, 0, 1), at(1, 0, 1), fx);
+        let c11 = lerp(at(0, 1, 1), at(1, 1, 1), fx);
+
+        lerp(lerp(c00, c10, fy), lerp(c01, c11, fy), fz)
+    }
+}
+
+// A heterogeneous medium whose density is read from a voxel grid. Scattering is sampled with
+// delta tracking and shadow rays use ratio tracking, both against the grid's maximum density.
+pub struct GridMedium {
+    density: Arc<VoxelGrid>,
+    scale: f64,
+    material: Arc<dyn Material>,
+}
+
+impl GridMedium {
+    pub fn new(density: VoxelGrid, phase: Arc<dyn Material>) -> Self {
+        Self {
+            density: Arc::new(density),
+            scale: 1.0,
+            material: phase,
+        }
+    }
+
+    pub fn with_density_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    // Adds blackbody emission from a grid of temperatures in kelvin. `intensity` scales the
+    // radiance emitted at each collision.
+    pub fn with_temperature(mut self, temperature: VoxelGrid, intensity: f64) -> Self {
+        self.material = Arc::new(Blackbody {
+            phase: self.material,
+            temperature,
+            intensity,
+        });
+        self
+    }
+
+    fn majorant(&self) -> f64 {
+        self.scale * self.density.max()
+    }
+
+    fn density(&self, p: &Vector3) -> f64 {
+        self.scale * self.density.value(p)
+    }
+}
+
+impl Hittable for GridMedium {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let majorant = self.majorant();
+        let (mut t, t1) = self.density.bounds().intersect(r, t_range)?;
+        if majorant <= 0.0 {
+            return None;
+        }
+
+        let step = 1.0 / (majorant * r.direction.norm());
+        loop {
+            t -= (1.0 - rand::random::<f64>()).ln() * step;
+            if t >= t1 {
+                return None;
+            }
+
+            let p = r.at(t);
+            if rand::random::<f64>() * majorant < self.density(&p) {
+                return Some(scatter_record(r, t, self.material.clone()));
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.density.bounds().clone())
+    }
+
+    fn hit_surface(&self, _: &Ray, _: Range<f64>) -> Option<HitRecord> {
+        None
+    }
+
+    fn transmittance(&self, r: &Ray, t_range: Range<f64>) -> f64 {
+        let majorant = self.majorant();
+        let (mut t, t1) = match self.density.bounds().intersect(r, t_range) {
+            Some(interval) if majorant > 0.0 => interval,
+            _ => return 1.0,
+        };
+
+        let step = 1.0 / (majorant * r.direction.norm());
+        let mut transmittance = 1.0;
+        loop {
+            t -= (1.0 - rand::random::<f64>()).ln() * step;
+            if t >= t1 {
+                return transmittance;
+            }
+
+            transmittance *= 1.0 - self.density(&r.at(t)) / majorant;
+
+            // Russian roulette once the estimate gets small.
+            if transmittance < 0.1 {
+                if rand::random::<f64>() < 0.5 {
+                    return 0.0;
+                }
+                transmittance *= 2.0;
+            }
+        }
+    }
+}
+
+struct Blackbody {
+    phase: Arc<dyn Material>,
+    temperature: VoxelGrid,
+    intensity: f64,
+}
+
+impl Material for Blackbody {
+    fn sample(&self, r: &Ray, rec: &HitRecord) -> Option<BsdfSample> {
+        self.phase.sample(r, rec)
+    }
+
+    fn eval(&self, r: &Ray, rec: &HitRecord, direction: &Vector3) -> Color {
+        self.phase.eval(r, rec, direction)
+    }
+
+    fn pdf(&self, r: &Ray, rec: &HitRecord, direction: &Vector3) -> f64 {
+        self.phase.pdf(r, rec, direction)
+    }
+
+    fn emitted(&self, rec: &HitRecord) -> Color {
+        self.intensity * Color::blackbody(self.temperature.value(&rec.p))
+    }
+}
@@ -0,0 +1,63 @@
+use super::*;
+use crate::aabb::Aabb;
+use crate::ray::Ray;
+use crate::transform::{Animation, Transform};
+use std::ops::Range;
+use std::sync::Arc;
+
+// Like `Transformed`, but the placement follows `animation` according to each ray's time.
+pub struct Keyframed<H: Hittable> {
+    object: H,
+    animation: Animation,
+}
+
+// A shared hittable moving along its own keyframes.
+pub type KeyframedInstance = Keyframed<Arc<dyn Hittable>>;
+
+impl<H: Hittable> Keyframed<H> {
+    pub fn new(object: H, animation: Animation) -> Self {
+        Self { object, animation }
+    }
+
+    fn to_world(transform: &Transform, mut hit: HitRecord) -> HitRecord {
+        hit.p = transform.point(&hit.p);
+        hit.n = transform.normal(&hit.n).normalized();
+        hit
+    }
+}
+
+impl<H: Hittable> Hittable for Keyframed<H> {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let transform = self.animation.at(r.time);
+        let local = transform.inverse().ray(r);
+        let hit = self.object.hit(&local, t_range)?;
+        Some(Self::to_world(&transform, hit))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let bbox = self.object.bounding_box()?;
+        Some(self.animation.bbox(&bbox))
+    }
+
+    fn hit_surface(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let transform = self.animation.at(r.time);
+        let local = transform.inverse().ray(r);
+        let hit = self.object.hit_surface(&local, t_range)?;
+        Some(Self::to_world(&transform, hit))
+    }
+
+    fn transmittance(&self, r: &Ray, t_range: Range<f64>) -> f64 {
+        let local = self.animation.at(r.time).inverse().ray(r);
+        self.object.transmittance(&local, t_range)
+    }
+
+    fn crossings(&self, r: &Ray, t_range: Range<f64>) -> Vec<HitRecord> {
+        let transform = self.animation.at(r.time);
+        let local = transform.inverse().ray(r);
+        let crossings = self.object.crossings(&local, t_range);
+        crossings
+            .into_iter()
+            .map(|hit| Self::to_world(&transform, hit))
+            .collect()
+    }
+}
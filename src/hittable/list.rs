@@ -1,4 +1,5 @@
 use super::*;
+use crate::aabb::Aabb;
 use crate::ray::Ray;
 use std::ops::Range;
 
@@ -26,6 +27,18 @@ impl HittableList {
     pub fn clear(&mut self) {
         self.0.clear();
     }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn into_vec(self) -> Vec<Box<dyn Hittable>> {
+        self.0
+    }
 }
 
 impl Hittable for HittableList {
@@ -35,6 +48,27 @@ impl Hittable for HittableList {
             .filter_map(|object| object.hit(r, t_range.clone()))
             .min_by(|x, y| x.t.total_cmp(&y.t))
     }
+
+    fn hit_surface(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        self.0
+            .iter()
+            .filter_map(|object| object.hit_surface(r, t_range.clone()))
+            .min_by(|x, y| x.t.total_cmp(&y.t))
+    }
+
+    fn transmittance(&self, r: &Ray, t_range: Range<f64>) -> f64 {
+        self.0
+            .iter()
+            .map(|object| object.transmittance(r, t_range.clone()))
+            .product()
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let (first, rest) = self.0.split_first()?;
+        rest.iter().try_fold(first.bounding_box()?, |acc, object| {
+            Some(acc.union(&object.bounding_box()?))
+        })
+    }
 }
 
 impl FromIterator<Box<dyn Hittable>> for HittableList {
@@ -0,0 +1,122 @@
+use super::*;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray::Ray;
+use std::ops::Range;
+use std::sync::Arc;
+
+// A homogeneous volume filling a closed `boundary`. Rays are scattered at an exponentially
+// distributed distance inside it, with `phase` describing the scattering.
+pub struct ConstantMedium<H: Hittable> {
+    boundary: H,
+    density: f64,
+    phase: Arc<dyn Material>,
+}
+
+impl<H: Hittable> ConstantMedium<H> {
+    pub fn new(boundary: H, density: f64, phase: Arc<dyn Material>) -> Self {
+        Self {
+            boundary,
+            density,
+            phase,
+        }
+    }
+
+    // The part of `t_range` inside the boundary.
+    fn interval(&self, r: &Ray, t_range: Range<f64>) -> Option<(f64, f64)> {
+        let enter = self.boundary.hit(r, f64::NEG_INFINITY..f64::INFINITY)?;
+        let exit = self.boundary.hit(r, enter.t + 1e-6..f64::INFINITY)?;
+
+        let t0 = enter.t.max(t_range.start);
+        let t1 = exit.t.min(t_range.end);
+        (t0 < t1).then_some((t0, t1))
+    }
+}
+
+impl<H: Hittable> Hittable for ConstantMedium<H> {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let (t0, t1) = self.interval(r, t_range)?;
+        let t = t0 + free_path(r, self.density);
+        (t < t1).then(|| scatter_record(r, t, self.phase.clone()))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.boundary.bounding_box()
+    }
+
+    fn hit_surface(&self, _: &Ray, _: Range<f64>) -> Option<HitRecord> {
+        None
+    }
+
+    fn transmittance(&self, r: &Ray, t_range: Range<f64>) -> f64 {
+        self.interval(r, t_range).map_or(1.0, |(t0, t1)| {
+            (-self.density * (t1 - t0) * r.direction.norm()).exp()
+        })
+    }
+}
+
+// A homogeneous medium filling the whole scene. Rays escaping the world travel `extent` through
+// it before reaching the background.
+pub struct Atmosphere {
+    density: f64,
+    phase: Arc<dyn Material>,
+    extent: f64,
+}
+
+impl Atmosphere {
+    pub fn new(density: f64, phase: Arc<dyn Material>) -> Self {
+        Self {
+            density,
+            phase,
+            extent: f64::INFINITY,
+        }
+    }
+
+    pub fn with_extent(mut self, extent: f64) -> Self {
+        self.extent = extent;
+        self
+    }
+
+    // Samples a scattering event before the surface hit `hit`, if any.
+    pub(super) fn scatter(
+        &self,
+        r: &Ray,
+        t_range: Range<f64>,
+        hit: Option<HitRecord>,
+    ) -> Option<HitRecord> {
+        let end = match &hit {
+            Some(hit) => hit.t,
+            None => self.end(r, &t_range),
+        };
+
+        let t = t_range.start + free_path(r, self.density);
+        if t < end {
+            Some(scatter_record(r, t, self.phase.clone()))
+        } else {
+            hit
+        }
+    }
+
+    pub(super) fn transmittance(&self, r: &Ray, t_range: Range<f64>) -> f64 {
+        let length = (self.end(r, &t_range) - t_range.start) * r.direction.norm();
+        (-self.density * length).exp()
+    }
+
+    // Where rays escaping the world leave the atmosphere.
+    fn end(&self, r: &Ray, t_range: &Range<f64>) -> f64 {
+        (t_range.start + self.extent / r.direction.norm()).min(t_range.end)
+    }
+}
+
+// Distance along `r`, in units of its parameter, to the next collision in a medium of `density`.
+fn free_path(r: &Ray, density: f64) -> f64 {
+    let distance = -(1.0 - rand::random::<f64>()).ln() / density;
+    distance / r.direction.norm()
+}
+
+pub(super) fn scatter_record(r: &Ray, t: f64, phase: Arc<dyn Material>) -> HitRecord {
+    let mut hit = HitRecord::new(r.at(t), -r.direction.normalized(), t, phase);
+    hit.front_face = true;
+    hit.in_medium = true;
+    hit
+}
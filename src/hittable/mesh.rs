@@ -0,0 +1,141 @@
+use super::triangle::{area, intersect, pdf_value, record, sample_point};
+use super::*;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+use std::ops::Range;
+use std::sync::Arc;
+
+struct MeshData {
+    positions: Vec<Vector3>,
+    normals: Option<Vec<Vector3>>,
+    uvs: Option<Vec<(f64, f64)>>,
+    indices: Vec<[usize; 3]>,
+    material: Arc<dyn Material>,
+    area: f64,
+}
+
+struct MeshTriangle {
+    mesh: Arc<MeshData>,
+    index: usize,
+}
+
+impl MeshTriangle {
+    fn vertices(&self) -> [&Vector3; 3] {
+        self.mesh.indices[self.index].map(|i| &self.mesh.positions[i])
+    }
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let vertices = self.vertices();
+        let (t, b1, b2) = intersect(vertices, r, t_range)?;
+        let indices = self.mesh.indices[self.index];
+        let normals = self.mesh.normals.as_ref().map(|ns| indices.map(|i| &ns[i]));
+        let uvs = self.mesh.uvs.as_ref().map(|uvs| indices.map(|i| &uvs[i]));
+
+        Some(record(
+            vertices,
+            normals,
+            uvs,
+            r,
+            t,
+            (b1, b2),
+            self.mesh.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Aabb::from_points(self.vertices())
+    }
+
+    fn pdf_value(&self, origin: &Vector3, direction: &Vector3) -> f64 {
+        pdf_value(self.vertices(), origin, direction, self.mesh.area)
+    }
+}
+
+pub struct TriangleMesh {
+    mesh: Arc<MeshData>,
+    cdf: Vec<f64>,
+    bvh: Bvh,
+}
+
+impl TriangleMesh {
+    pub fn new(
+        positions: Vec<Vector3>,
+        normals: Option<Vec<Vector3>>,
+        uvs: Option<Vec<(f64, f64)>>,
+        indices: Vec<[usize; 3]>,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        assert!(indices.iter().flatten().all(|&i| i < positions.len()));
+        assert!(normals
+            .as_ref()
+            .is_none_or(|ns| ns.len() == positions.len()));
+        assert!(uvs.as_ref().is_none_or(|uvs| uvs.len() == positions.len()));
+
+        let normals = normals.map(|ns| ns.iter().map(Vector3::normalized).collect());
+        let count = indices.len();
+        let cdf: Vec<f64> = indices
+            .iter()
+            .scan(0.0, |acc, face| {
+                *acc += area(face.map(|i| &positions[i]));
+                Some(*acc)
+            })
+            .collect();
+
+        let mesh = Arc::new(MeshData {
+            positions,
+            normals,
+            uvs,
+            indices,
+            material,
+            area: cdf.last().copied().unwrap_or(0.0),
+        });
+
+        let triangles = (0..count)
+            .map(|index| {
+                Box::new(MeshTriangle {
+                    mesh: mesh.clone(),
+                    index,
+                }) as Box<dyn Hittable>
+            })
+            .collect();
+
+        Self {
+            mesh,
+            cdf,
+            bvh: Bvh::new(triangles),
+        }
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        self.bvh.hit(r, t_range)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.bvh.bounding_box()
+    }
+
+    fn is_sampled(&self) -> bool {
+        true
+    }
+
+    fn pdf_value(&self, origin: &Vector3, direction: &Vector3) -> f64 {
+        self.bvh.pdf_sum(origin, direction)
+    }
+
+    fn sample_direction(&self, origin: &Vector3) -> Vector3 {
+        let target = rand::random::<f64>() * self.mesh.area;
+        let index = self
+            .cdf
+            .partition_point(|&c| c < target)
+            .min(self.cdf.len() - 1);
+        let face = self.mesh.indices[index];
+
+        sample_point(face.map(|i| &self.mesh.positions[i])) - origin
+    }
+}
@@ -1,15 +1,21 @@
+use crate::aabb::Aabb;
 use crate::material::Material;
 use crate::ray::Ray;
 use crate::vector3::Vector3;
 use std::ops::Range;
 use std::sync::Arc;
 
+#[derive(Clone)]
 pub struct HitRecord {
     pub p: Vector3,
     pub n: Vector3,
     pub t: f64,
+    pub u: f64,
+    pub v: f64,
     pub material: Arc<dyn Material>,
     pub front_face: bool,
+    // Collisions inside participating media, whose emission light sampling never gathers.
+    pub in_medium: bool,
 }
 
 impl HitRecord {
@@ -18,11 +24,20 @@ impl HitRecord {
             p,
             n,
             t,
+            u: 0.0,
+            v: 0.0,
             material,
             front_face: false,
+            in_medium: false,
         }
     }
 
+    pub fn with_uv(mut self, u: f64, v: f64) -> Self {
+        self.u = u;
+        self.v = v;
+        self
+    }
+
     pub fn set_face(mut self, r: &Ray) -> Self {
         self.front_face = r.direction.dot(&self.n) < 0.0;
         self.n = if self.front_face { self.n } else { -self.n };
@@ -32,12 +47,119 @@ impl HitRecord {
 
 pub trait Hittable: Send + Sync {
     fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord>;
+
+    fn bounding_box(&self) -> Option<Aabb>;
+
+    // Like `hit`, but passes through participating media, which are accounted for by
+    // `transmittance` instead.
+    fn hit_surface(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        self.hit(r, t_range)
+    }
+
+    // Fraction of light crossing the participating media along `r` within `t_range`.
+    fn transmittance(&self, _r: &Ray, _t_range: Range<f64>) -> f64 {
+        1.0
+    }
+
+    // Every surface crossing along `r` within `t_range`, nearest first. For closed objects
+    // they alternate between entering (front face) and leaving, which CSG relies on.
+    fn crossings(&self, r: &Ray, t_range: Range<f64>) -> Vec<HitRecord> {
+        let mut crossings = Vec::new();
+        let mut start = t_range.start;
+        while let Some(hit) = self.hit(r, start..t_range.end) {
+            start = hit.t + 1e-6;
+            crossings.push(hit);
+        }
+        crossings
+    }
+
+    // Hittables that can be sampled as area lights; the others have no density towards them
+    // and are only found by scattered rays.
+    fn is_sampled(&self) -> bool {
+        false
+    }
+
+    fn pdf_value(&self, _origin: &Vector3, _direction: &Vector3) -> f64 {
+        0.0
+    }
+
+    fn sample_direction(&self, _origin: &Vector3) -> Vector3 {
+        Vector3::new(1.0, 0.0, 0.0)
+    }
+}
+
+impl<T: Hittable + ?Sized> Hittable for Arc<T> {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        (**self).hit(r, t_range)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        (**self).bounding_box()
+    }
+
+    fn hit_surface(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        (**self).hit_surface(r, t_range)
+    }
+
+    fn transmittance(&self, r: &Ray, t_range: Range<f64>) -> f64 {
+        (**self).transmittance(r, t_range)
+    }
+
+    fn crossings(&self, r: &Ray, t_range: Range<f64>) -> Vec<HitRecord> {
+        (**self).crossings(r, t_range)
+    }
+
+    fn is_sampled(&self) -> bool {
+        (**self).is_sampled()
+    }
+
+    fn pdf_value(&self, origin: &Vector3, direction: &Vector3) -> f64 {
+        (**self).pdf_value(origin, direction)
+    }
+
+    fn sample_direction(&self, origin: &Vector3) -> Vector3 {
+        (**self).sample_direction(origin)
+    }
 }
 
+pub mod bvh;
+pub mod cone;
+pub mod csg;
+pub mod cuboid;
+pub mod cylinder;
+pub mod disk;
+pub mod distance_field;
+pub mod environment;
+pub mod grid;
+pub mod keyframed;
 pub mod list;
+pub mod medium;
+pub mod mesh;
+pub mod moving_sphere;
+pub mod plane;
+pub mod quad;
 pub mod sphere;
-pub mod environment;
+pub mod torus;
+pub mod transformed;
+pub mod triangle;
 
+pub use bvh::*;
+pub use cone::*;
+pub use csg::*;
+pub use cuboid::*;
+pub use cylinder::*;
+pub use disk::*;
+pub use distance_field::*;
+pub use environment::*;
+pub use grid::*;
+pub use keyframed::*;
 pub use list::*;
+pub use medium::*;
+pub use mesh::*;
+pub use moving_sphere::*;
+pub use plane::*;
+pub use quad::*;
 pub use sphere::*;
-pub use environment::*;
+pub use torus::*;
+pub use transformed::*;
+pub use triangle::*;
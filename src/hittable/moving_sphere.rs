@@ -0,0 +1,66 @@
+use super::sphere::hit_sphere;
+use super::*;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+use std::ops::Range;
+use std::sync::Arc;
+
+// A sphere whose center moves linearly from `center0` at `time0` to `center1` at `time1`.
+pub struct MovingSphere {
+    center0: Vector3,
+    center1: Vector3,
+    time0: f64,
+    time1: f64,
+    radius: f64,
+    material: Arc<dyn Material>,
+}
+
+impl MovingSphere {
+    pub fn new(
+        center0: Vector3,
+        center1: Vector3,
+        time0: f64,
+        time1: f64,
+        radius: f64,
+        material: Arc<dyn Material + Send + Sync>,
+    ) -> Self {
+        Self {
+            center0,
+            center1,
+            time0,
+            time1,
+            radius,
+            material,
+        }
+    }
+
+    pub fn center(&self, time: f64) -> Vector3 {
+        if self.time1 == self.time0 {
+            return self.center0.clone();
+        }
+
+        let s = ((time - self.time0) / (self.time1 - self.time0)).clamp(0.0, 1.0);
+        &self.center0 + s * (&self.center1 - &self.center0)
+    }
+}
+
+impl Hittable for MovingSphere {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        hit_sphere(
+            &self.center(r.time),
+            self.radius,
+            &self.material,
+            r,
+            t_range,
+        )
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let r = Vector3::new(self.radius, self.radius, self.radius);
+        let box0 = Aabb::new(&self.center0 - &r, &self.center0 + &r);
+        let box1 = Aabb::new(&self.center1 - &r, &self.center1 + &r);
+        Some(box0.union(&box1))
+    }
+}
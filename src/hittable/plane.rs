@@ -0,0 +1,55 @@
+use super::*;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::transform::Transform;
+use crate::vector3::Vector3;
+use std::ops::Range;
+use std::sync::Arc;
+
+// An infinite plane. It has no bounding box, so acceleration structures keep it aside.
+pub struct Plane {
+    point: Vector3,
+    normal: Vector3,
+    to_local: Transform,
+    material: Arc<dyn Material>,
+}
+
+impl Plane {
+    pub fn new(point: Vector3, normal: Vector3, material: Arc<dyn Material>) -> Self {
+        let normal = normal.normalized();
+        Self {
+            to_local: Transform::orient(&point, &normal).inverse(),
+            point,
+            normal,
+            material,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let denom = self.normal.dot(&r.direction);
+        if denom.abs() < 1e-12 {
+            return None;
+        }
+
+        let t = (&self.point - &r.origin).dot(&self.normal) / denom;
+        if !t_range.contains(&t) {
+            return None;
+        }
+
+        // Textures repeat once per unit length along the plane.
+        let p = r.at(t);
+        let local = self.to_local.point(&p);
+        Some(
+            HitRecord::new(p, self.normal.clone(), t, self.material.clone())
+                .with_uv(local.x.rem_euclid(1.0), local.z.rem_euclid(1.0))
+                .set_face(r),
+        )
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
+}
@@ -0,0 +1,88 @@
+use super::*;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+use std::ops::Range;
+use std::sync::Arc;
+
+// The parallelogram spanned by `u` and `v` from the corner `q`.
+pub struct Quad {
+    q: Vector3,
+    u: Vector3,
+    v: Vector3,
+    normal: Vector3,
+    // Projects a point in the plane onto (u, v) coordinates.
+    w: Vector3,
+    area: f64,
+    material: Arc<dyn Material>,
+}
+
+impl Quad {
+    pub fn new(q: Vector3, u: Vector3, v: Vector3, material: Arc<dyn Material>) -> Self {
+        let n = u.cross(&v);
+        let w = &n / n.norm_squared();
+        Self {
+            normal: n.normalized(),
+            area: n.norm(),
+            w,
+            q,
+            u,
+            v,
+            material,
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let denom = self.normal.dot(&r.direction);
+        if denom.abs() < 1e-12 {
+            return None;
+        }
+
+        let t = (&self.q - &r.origin).dot(&self.normal) / denom;
+        if !t_range.contains(&t) {
+            return None;
+        }
+
+        let p = r.at(t);
+        let d = &p - &self.q;
+        let alpha = self.w.dot(&d.cross(&self.v));
+        let beta = self.w.dot(&self.u.cross(&d));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        Some(
+            HitRecord::new(p, self.normal.clone(), t, self.material.clone())
+                .with_uv(alpha, beta)
+                .set_face(r),
+        )
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let (q, u, v) = (&self.q, &self.u, &self.v);
+        Aabb::from_points(&[q.clone(), q + u, q + v, q + u + v])
+    }
+
+    fn is_sampled(&self) -> bool {
+        true
+    }
+
+    fn pdf_value(&self, origin: &Vector3, direction: &Vector3) -> f64 {
+        let r = Ray::new(origin.clone(), direction.normalized());
+        match self.hit(&r, 1e-6..f64::INFINITY) {
+            Some(hit) => {
+                let cos = self.normal.dot(&r.direction).abs();
+                hit.t * hit.t / (cos * self.area)
+            }
+            None => 0.0,
+        }
+    }
+
+    fn sample_direction(&self, origin: &Vector3) -> Vector3 {
+        let (xi1, xi2) = rand::random::<(f64, f64)>();
+        &self.q + xi1 * &self.u + xi2 * &self.v - origin
+    }
+}
@@ -1,5 +1,7 @@
 use super::*;
+use crate::aabb::Aabb;
 use crate::material::Material;
+use crate::random::random_unit_vector;
 use crate::ray::Ray;
 use crate::vector3::Vector3;
 use std::ops::Range;
@@ -23,23 +25,118 @@ impl Sphere {
 
 impl Hittable for Sphere {
     fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
-        let oc = &r.origin - &self.center;
-        let a = r.direction.norm_squared();
-        let b = oc.dot(&r.direction);
-        let c = oc.norm_squared() - self.radius * self.radius;
-        let d = b * b - a * c;
-
-        if d < 0.0 {
-            return None;
-        }
+        hit_sphere(&self.center, self.radius, &self.material, r, t_range)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let r = Vector3::new(self.radius, self.radius, self.radius);
+        Some(Aabb::new(&self.center - &r, &self.center + &r))
+    }
 
-        [(-b - d.sqrt()) / a, (-b + d.sqrt()) / a]
+    fn crossings(&self, r: &Ray, t_range: Range<f64>) -> Vec<HitRecord> {
+        let roots = sphere_roots(&self.center, self.radius, r);
+        roots
             .into_iter()
-            .find(|t| t_range.contains(t))
-            .map(|t| {
-                let p = r.at(t);
-                let n = (&p - &self.center) / self.radius;
-                HitRecord::new(p, n, t, self.material.clone()).set_face(r)
-            })
+            .flatten()
+            .filter(|t| t_range.contains(t))
+            .map(|t| sphere_record(&self.center, self.radius, &self.material, r, t))
+            .collect()
+    }
+
+    fn is_sampled(&self) -> bool {
+        true
+    }
+
+    fn pdf_value(&self, origin: &Vector3, direction: &Vector3) -> f64 {
+        let r = Ray::new(origin.clone(), direction.normalized());
+        let hit = match self.hit(&r, 1e-6..f64::INFINITY) {
+            Some(hit) => hit,
+            None => return 0.0,
+        };
+
+        let d2 = (&self.center - origin).norm_squared();
+        let r2 = self.radius * self.radius;
+
+        if d2 <= r2 {
+            // Inside the sphere the whole surface is sampled by area.
+            let cos = hit.n.dot(&r.direction).abs();
+            let area = 4.0 * std::f64::consts::PI * r2;
+            hit.t * hit.t / (cos * area)
+        } else {
+            let cos_max = (1.0 - r2 / d2).sqrt();
+            1.0 / (2.0 * std::f64::consts::PI * (1.0 - cos_max))
+        }
     }
+
+    fn sample_direction(&self, origin: &Vector3) -> Vector3 {
+        let axis = &self.center - origin;
+        let d2 = axis.norm_squared();
+        let r2 = self.radius * self.radius;
+
+        if d2 <= r2 {
+            return &self.center + self.radius * random_unit_vector() - origin;
+        }
+
+        let cos_max = (1.0 - r2 / d2).sqrt();
+        let (xi1, xi2) = rand::random::<(f64, f64)>();
+        let z = 1.0 + xi1 * (cos_max - 1.0);
+        let sin = (1.0 - z * z).max(0.0).sqrt();
+        let phi = 2.0 * std::f64::consts::PI * xi2;
+
+        let w = axis / d2.sqrt();
+        let (u, v) = w.orthonormal_basis();
+        sin * phi.cos() * u + sin * phi.sin() * v + z * w
+    }
+}
+
+pub(super) fn hit_sphere(
+    center: &Vector3,
+    radius: f64,
+    material: &Arc<dyn Material>,
+    r: &Ray,
+    t_range: Range<f64>,
+) -> Option<HitRecord> {
+    sphere_roots(center, radius, r)?
+        .into_iter()
+        .find(|t| t_range.contains(t))
+        .map(|t| sphere_record(center, radius, material, r, t))
+}
+
+fn sphere_roots(center: &Vector3, radius: f64, r: &Ray) -> Option<[f64; 2]> {
+    let oc = &r.origin - center;
+    let a = r.direction.norm_squared();
+    let b = oc.dot(&r.direction);
+    let c = oc.norm_squared() - radius * radius;
+    let d = b * b - a * c;
+
+    if d < 0.0 {
+        return None;
+    }
+
+    Some([(-b - d.sqrt()) / a, (-b + d.sqrt()) / a])
+}
+
+fn sphere_record(
+    center: &Vector3,
+    radius: f64,
+    material: &Arc<dyn Material>,
+    r: &Ray,
+    t: f64,
+) -> HitRecord {
+    let p = r.at(t);
+    let n = (&p - center) / radius;
+    let (u, v) = sphere_uv(&n);
+    HitRecord::new(p, n, t, material.clone())
+        .with_uv(u, v)
+        .set_face(r)
+}
+
+fn sphere_uv(n: &Vector3) -> (f64, f64) {
+    let theta = (-n.y).clamp(-1.0, 1.0).acos();
+    let phi = (-n.z).atan2(n.x) + std::f64::consts::PI;
+
+    (
+        phi / (2.0 * std::f64::consts::PI),
+        theta / std::f64::consts::PI,
+    )
 }
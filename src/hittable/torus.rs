@@ -0,0 +1,157 @@
+use super::disk::{azimuth, disk_bbox};
+use super::*;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::transform::Transform;
+use crate::vector3::Vector3;
+use std::f64::consts::PI;
+use std::ops::Range;
+use std::sync::Arc;
+
+// A ring around `axis` with the tube of radius `minor` centered `major` away from `center`.
+// In local space it lies in the xz plane.
+pub struct Torus {
+    center: Vector3,
+    axis: Vector3,
+    major: f64,
+    minor: f64,
+    transform: Transform,
+    to_local: Transform,
+    material: Arc<dyn Material>,
+}
+
+impl Torus {
+    pub fn new(
+        center: Vector3,
+        axis: Vector3,
+        major: f64,
+        minor: f64,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        let transform = Transform::orient(&center, &axis);
+        Self {
+            to_local: transform.inverse(),
+            transform,
+            center,
+            axis,
+            major,
+            minor,
+            material,
+        }
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let local = self.to_local.ray(r);
+        let scale = local.direction.norm();
+        let d = &local.direction / scale;
+
+        // Solve from the point closest to the center, where the roots lie within the outer
+        // radius, to keep the quartic well conditioned.
+        let reach = self.major + self.minor;
+        let s0 = -local.origin.dot(&d);
+        let o = &local.origin + s0 * &d;
+        if o.norm_squared() > reach * reach {
+            return None;
+        }
+
+        // (|p|² + R² - r²)² = 4R²(x² + z²) along p = o + s d.
+        let (r2, big_r2) = (self.minor * self.minor, self.major * self.major);
+        let k = o.norm_squared() + big_r2 - r2;
+        let od = o.x * d.x + o.z * d.z;
+        let dd = d.x * d.x + d.z * d.z;
+        let oo = o.x * o.x + o.z * o.z;
+        let coefficients = [
+            k * k - 4.0 * big_r2 * oo,
+            -8.0 * big_r2 * od,
+            2.0 * k - 4.0 * big_r2 * dd,
+            0.0,
+            1.0,
+        ];
+
+        let t = real_roots(&coefficients, -reach, reach)
+            .into_iter()
+            .map(|s| (s + s0) / scale)
+            .find(|t| t_range.contains(t))?;
+
+        let p = local.at(t);
+        let k = p.norm_squared() + big_r2 - r2;
+        let n = Vector3::new(p.x * (k - 2.0 * big_r2), p.y * k, p.z * (k - 2.0 * big_r2));
+
+        let ring = p.x.hypot(p.z) - self.major;
+        let v = (p.y.atan2(ring) + PI) / (2.0 * PI);
+
+        Some(
+            HitRecord::new(
+                r.at(t),
+                self.transform.vector(&n).normalized(),
+                t,
+                self.material.clone(),
+            )
+            .with_uv(azimuth(p.x, p.z), v)
+            .set_face(r),
+        )
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let ring = disk_bbox(&self.center, &self.axis, self.major);
+        let pad = Vector3::new(self.minor, self.minor, self.minor);
+        Some(Aabb::new(&ring.min - &pad, &ring.max + &pad))
+    }
+}
+
+// Sorted real roots in [lo, hi] of the polynomial with the given coefficients, constant
+// term first. Roots of the derivative split the interval into monotonic pieces, each holding
+// at most one root, which is then found by bisection.
+fn real_roots(coefficients: &[f64], lo: f64, hi: f64) -> Vec<f64> {
+    let eval = |x: f64| coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c);
+
+    if coefficients.len() <= 2 {
+        return match coefficients {
+            [c0, c1] if *c1 != 0.0 => Some(-c0 / c1).filter(|x| (lo..=hi).contains(x)),
+            _ => None,
+        }
+        .into_iter()
+        .collect();
+    }
+
+    let derivative: Vec<f64> = coefficients
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| i as f64 * c)
+        .collect();
+
+    let mut bounds = vec![lo];
+    bounds.extend(real_roots(&derivative, lo, hi));
+    bounds.push(hi);
+
+    bounds
+        .windows(2)
+        .filter_map(|w| {
+            let (mut a, mut b) = (w[0], w[1]);
+            let (fa, fb) = (eval(a), eval(b));
+            if fa == 0.0 {
+                return Some(a);
+            }
+            if fa.signum() == fb.signum() {
+                return None;
+            }
+
+            for _ in 0..64 {
+                let m = 0.5 * (a + b);
+                if m <= a || m >= b {
+                    break;
+                }
+                if eval(m).signum() == fa.signum() {
+                    a = m;
+                } else {
+                    b = m;
+                }
+            }
+            Some(0.5 * (a + b))
+        })
+        .collect()
+}
@@ -0,0 +1,89 @@
+use super::*;
+use crate::aabb::Aabb;
+use crate::ray::Ray;
+use crate::transform::Transform;
+use crate::vector3::Vector3;
+use std::ops::Range;
+use std::sync::Arc;
+
+// Places `object` in the world by `transform`. Rays are moved into object space, so the
+// wrapped geometry is never copied.
+pub struct Transformed<H: Hittable> {
+    object: H,
+    transform: Transform,
+    to_local: Transform,
+}
+
+// A shared hittable placed once; any number of instances may point at the same object.
+pub type Instance = Transformed<Arc<dyn Hittable>>;
+
+impl<H: Hittable> Transformed<H> {
+    pub fn new(object: H, transform: Transform) -> Self {
+        Self {
+            object,
+            to_local: transform.inverse(),
+            transform,
+        }
+    }
+
+    fn to_world(&self, mut hit: HitRecord) -> HitRecord {
+        hit.p = self.transform.point(&hit.p);
+        hit.n = self.transform.normal(&hit.n).normalized();
+        hit
+    }
+}
+
+impl<H: Hittable> Hittable for Transformed<H> {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let local = self.to_local.ray(r);
+        let hit = self.object.hit(&local, t_range)?;
+        Some(self.to_world(hit))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let bbox = self.object.bounding_box()?;
+        Some(self.transform.bbox(&bbox))
+    }
+
+    fn hit_surface(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let local = self.to_local.ray(r);
+        let hit = self.object.hit_surface(&local, t_range)?;
+        Some(self.to_world(hit))
+    }
+
+    fn transmittance(&self, r: &Ray, t_range: Range<f64>) -> f64 {
+        let local = self.to_local.ray(r);
+        self.object.transmittance(&local, t_range)
+    }
+
+    fn crossings(&self, r: &Ray, t_range: Range<f64>) -> Vec<HitRecord> {
+        let local = self.to_local.ray(r);
+        let crossings = self.object.crossings(&local, t_range);
+        crossings
+            .into_iter()
+            .map(|hit| self.to_world(hit))
+            .collect()
+    }
+
+    fn is_sampled(&self) -> bool {
+        self.object.is_sampled()
+    }
+
+    // The object's density is per unit solid angle in object space; a linear map `A` scales
+    // solid angle around the unit direction `w` by |det A| / |A w|^3.
+    fn pdf_value(&self, origin: &Vector3, direction: &Vector3) -> f64 {
+        let local = self.to_local.vector(direction).normalized();
+        let pdf = self.object.pdf_value(&self.to_local.point(origin), &local);
+        if pdf <= 0.0 {
+            return 0.0;
+        }
+
+        let stretch = self.transform.vector(&local).norm();
+        pdf * stretch.powi(3) / self.transform.matrix().determinant3().abs()
+    }
+
+    fn sample_direction(&self, origin: &Vector3) -> Vector3 {
+        let local = self.object.sample_direction(&self.to_local.point(origin));
+        self.transform.vector(&local)
+    }
+}
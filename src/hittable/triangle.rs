@@ -0,0 +1,181 @@
+use super::*;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+use std::ops::Range;
+use std::sync::Arc;
+
+pub struct Triangle {
+    vertices: [Vector3; 3],
+    normals: Option<[Vector3; 3]>,
+    uvs: Option<[(f64, f64); 3]>,
+    material: Arc<dyn Material>,
+}
+
+impl Triangle {
+    pub fn new(a: Vector3, b: Vector3, c: Vector3, material: Arc<dyn Material>) -> Self {
+        Self {
+            vertices: [a, b, c],
+            normals: None,
+            uvs: None,
+            material,
+        }
+    }
+
+    pub fn with_normals(mut self, normals: [Vector3; 3]) -> Self {
+        self.normals = Some(normals.map(|n| n.normalized()));
+        self
+    }
+
+    pub fn with_uvs(mut self, uvs: [(f64, f64); 3]) -> Self {
+        self.uvs = Some(uvs);
+        self
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, r: &Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let [a, b, c] = &self.vertices;
+        let (t, b1, b2) = intersect([a, b, c], r, t_range)?;
+        let normals = self.normals.as_ref().map(|[n0, n1, n2]| [n0, n1, n2]);
+        let uvs = self.uvs.as_ref().map(|[uv0, uv1, uv2]| [uv0, uv1, uv2]);
+
+        Some(record(
+            [a, b, c],
+            normals,
+            uvs,
+            r,
+            t,
+            (b1, b2),
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Aabb::from_points(&self.vertices)
+    }
+
+    fn is_sampled(&self) -> bool {
+        true
+    }
+
+    fn pdf_value(&self, origin: &Vector3, direction: &Vector3) -> f64 {
+        let [a, b, c] = &self.vertices;
+        pdf_value([a, b, c], origin, direction, area([a, b, c]))
+    }
+
+    fn sample_direction(&self, origin: &Vector3) -> Vector3 {
+        let [a, b, c] = &self.vertices;
+        sample_point([a, b, c]) - origin
+    }
+}
+
+pub(super) fn area([a, b, c]: [&Vector3; 3]) -> f64 {
+    0.5 * (b - a).cross(&(c - a)).norm()
+}
+
+pub(super) fn sample_point([a, b, c]: [&Vector3; 3]) -> Vector3 {
+    let (xi1, xi2) = rand::random::<(f64, f64)>();
+    let s = xi1.sqrt();
+    let (b0, b1) = (1.0 - s, xi2 * s);
+
+    b0 * a + b1 * b + (1.0 - b0 - b1) * c
+}
+
+// Solid angle density of a direction towards the triangle, when its surface is sampled
+// uniformly with respect to `area`.
+pub(super) fn pdf_value(
+    vertices: [&Vector3; 3],
+    origin: &Vector3,
+    direction: &Vector3,
+    area: f64,
+) -> f64 {
+    let [a, b, c] = vertices;
+    let r = Ray::new(origin.clone(), direction.normalized());
+
+    match intersect(vertices, &r, 1e-6..f64::INFINITY) {
+        Some((t, _, _)) => {
+            let n = (b - a).cross(&(c - a)).normalized();
+            let cos = n.dot(&r.direction).abs();
+
+            if cos < 1e-8 {
+                0.0
+            } else {
+                t * t / (cos * area)
+            }
+        }
+        None => 0.0,
+    }
+}
+
+// Möller–Trumbore; returns the ray parameter and the barycentrics of the second and third vertex.
+pub(super) fn intersect(
+    [a, b, c]: [&Vector3; 3],
+    r: &Ray,
+    t_range: Range<f64>,
+) -> Option<(f64, f64, f64)> {
+    let e1 = b - a;
+    let e2 = c - a;
+    let p = r.direction.cross(&e2);
+    let det = e1.dot(&p);
+
+    if det.abs() < 1e-12 {
+        return None;
+    }
+
+    let inv_det = 1.0 / det;
+    let s = &r.origin - a;
+    let b1 = s.dot(&p) * inv_det;
+
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let q = s.cross(&e1);
+    let b2 = r.direction.dot(&q) * inv_det;
+
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    let t = e2.dot(&q) * inv_det;
+    t_range.contains(&t).then_some((t, b1, b2))
+}
+
+pub(super) fn record(
+    [a, b, c]: [&Vector3; 3],
+    normals: Option<[&Vector3; 3]>,
+    uvs: Option<[&(f64, f64); 3]>,
+    r: &Ray,
+    t: f64,
+    (b1, b2): (f64, f64),
+    material: Arc<dyn Material>,
+) -> HitRecord {
+    let b0 = 1.0 - b1 - b2;
+    let geometric = (b - a).cross(&(c - a)).normalized();
+
+    let n = match normals {
+        Some([n0, n1, n2]) => {
+            let n = (b0 * n0 + b1 * n1 + b2 * n2).normalized();
+            if n.dot(&geometric) < 0.0 {
+                -n
+            } else {
+                n
+            }
+        }
+        None => geometric,
+    };
+
+    let (u, v) = match uvs {
+        Some([uv0, uv1, uv2]) => (
+            b0 * uv0.0 + b1 * uv1.0 + b2 * uv2.0,
+            b0 * uv0.1 + b1 * uv1.1 + b2 * uv2.1,
+        ),
+        None => (b1, b2),
+    };
+
+    HitRecord::new(r.at(t), n, t, material)
+        .with_uv(u, v)
+        .set_face(r)
+}
@@ -1,8 +1,19 @@
+pub mod aabb;
+pub mod background;
 pub mod camera;
 pub mod color;
+pub mod distribution;
 pub mod hittable;
+pub mod light;
+pub mod loader;
 pub mod material;
+pub mod matrix4;
+pub mod quaternion;
 pub mod random;
 pub mod ray;
 pub mod render;
+pub mod sdf;
+pub mod texture;
+pub mod tonemap;
+pub mod transform;
 pub mod vector3;
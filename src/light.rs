@@ -0,0 +1,169 @@
+use crate::color::Color;
+use crate::vector3::Vector3;
+use std::f64::consts::PI;
+
+// Light arriving at a point from a punctual light.
+pub struct LightSample {
+    // Unit direction from the point towards the light.
+    pub direction: Vector3,
+    // Distance to the light, infinite for directional lights.
+    pub distance: f64,
+    // Irradiance on a surface facing the light, before any shadowing.
+    pub irradiance: Color,
+}
+
+// Lights without extent, which rays can never hit by chance and so are only found by light
+// sampling.
+pub trait Light: Send + Sync {
+    fn sample(&self, p: &Vector3) -> Option<LightSample>;
+}
+
+// Emits `intensity` (radiant intensity, power per steradian) equally in every direction.
+pub struct PointLight {
+    position: Vector3,
+    intensity: Color,
+}
+
+impl PointLight {
+    pub fn new(position: Vector3, intensity: Color) -> Self {
+        Self {
+            position,
+            intensity,
+        }
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, p: &Vector3) -> Option<LightSample> {
+        let to_light = &self.position - p;
+        let d2 = to_light.norm_squared();
+        if d2 <= 0.0 {
+            return None;
+        }
+
+        let distance = d2.sqrt();
+        Some(LightSample {
+            direction: to_light / distance,
+            distance,
+            irradiance: &self.intensity / d2,
+        })
+    }
+}
+
+// A point light restricted to a cone around `direction`, fading out smoothly between the inner
+// and outer cone angles. An optional profile scales the intensity by the angle from the axis.
+pub struct SpotLight {
+    position: Vector3,
+    direction: Vector3,
+    intensity: Color,
+    cos_inner: f64,
+    cos_outer: f64,
+    profile: Vec<(f64, f64)>,
+}
+
+impl SpotLight {
+    pub fn new(position: Vector3, direction: Vector3, intensity: Color) -> Self {
+        Self {
+            position,
+            direction: direction.normalized(),
+            intensity,
+            cos_inner: (PI / 6.0).cos(),
+            cos_outer: (PI / 4.0).cos(),
+            profile: Vec::new(),
+        }
+    }
+
+    // Angles from the axis in radians: full intensity up to `inner`, none beyond `outer`.
+    pub fn with_cone(mut self, inner: f64, outer: f64) -> Self {
+        assert!(0.0 <= inner && inner <= outer && outer <= PI);
+        self.cos_inner = inner.cos();
+        self.cos_outer = outer.cos();
+        self
+    }
+
+    // Relative intensity at increasing angles from the axis in radians, as in the vertical
+    // angles and candela values of an IES photometric file. Angles in between are interpolated
+    // linearly and those past either end take the nearest value.
+    pub fn with_profile(mut self, profile: Vec<(f64, f64)>) -> Self {
+        assert!(!profile.is_empty());
+        assert!(profile.windows(2).all(|w| w[0].0 < w[1].0));
+        self.profile = profile;
+        self
+    }
+
+    fn falloff(&self, cos: f64) -> f64 {
+        if cos >= self.cos_inner {
+            return 1.0;
+        }
+
+        let t = ((cos - self.cos_outer) / (self.cos_inner - self.cos_outer)).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    fn profile(&self, angle: f64) -> f64 {
+        let (first, last) = match (self.profile.first(), self.profile.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return 1.0,
+        };
+        if angle <= first.0 {
+            return first.1;
+        }
+        if angle >= last.0 {
+            return last.1;
+        }
+
+        let i = self.profile.partition_point(|&(a, _)| a <= angle);
+        let ((a0, v0), (a1, v1)) = (self.profile[i - 1], self.profile[i]);
+        v0 + (v1 - v0) * (angle - a0) / (a1 - a0)
+    }
+}
+
+impl Light for SpotLight {
+    fn sample(&self, p: &Vector3) -> Option<LightSample> {
+        let to_light = &self.position - p;
+        let d2 = to_light.norm_squared();
+        if d2 <= 0.0 {
+            return None;
+        }
+
+        let distance = d2.sqrt();
+        let direction = to_light / distance;
+        let cos = -direction.dot(&self.direction);
+        let scale = self.falloff(cos) * self.profile(cos.clamp(-1.0, 1.0).acos());
+        if scale <= 0.0 {
+            return None;
+        }
+
+        Some(LightSample {
+            direction,
+            distance,
+            irradiance: scale * &self.intensity / d2,
+        })
+    }
+}
+
+// Parallel light travelling along `direction`, such as sunlight, with the given irradiance on
+// surfaces facing it.
+pub struct DirectionalLight {
+    direction: Vector3,
+    irradiance: Color,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Vector3, irradiance: Color) -> Self {
+        Self {
+            direction: direction.normalized(),
+            irradiance,
+        }
+    }
+}
+
+impl Light for DirectionalLight {
+    fn sample(&self, _p: &Vector3) -> Option<LightSample> {
+        Some(LightSample {
+            direction: -&self.direction,
+            distance: f64::INFINITY,
+            irradiance: self.irradiance.clone(),
+        })
+    }
+}
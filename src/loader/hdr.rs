@@ -0,0 +1,51 @@
+use super::LoadError;
+use crate::color::Color;
+use image::codecs::hdr::HdrDecoder;
+use image::DynamicImage;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+// Reads an image as linear radiance: .hdr through its own decoder, which the generic path
+// would clamp to 8 bits, and anything else through `image`, where only float formats such as
+// .exr are taken as linear. Returns the width, height and row-major pixels.
+pub fn load_hdr<P: AsRef<Path>>(path: P) -> Result<(usize, usize, Vec<Color>), LoadError> {
+    let path = path.as_ref();
+    let radiance = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("hdr"));
+
+    if radiance {
+        let file = File::open(path).map_err(|e| LoadError::io(path, e))?;
+        let decoder =
+            HdrDecoder::new(BufReader::new(file)).map_err(|e| LoadError::image(path, e))?;
+        let meta = decoder.metadata();
+        let pixels = decoder
+            .read_image_hdr()
+            .map_err(|e| LoadError::image(path, e))?
+            .into_iter()
+            .map(|p| Color::new(p[0] as f64, p[1] as f64, p[2] as f64))
+            .collect();
+        return Ok((meta.width as usize, meta.height as usize, pixels));
+    }
+
+    let img = image::open(path).map_err(|e| LoadError::image(path, e))?;
+    let linear = matches!(
+        img,
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+    );
+    let img = img.into_rgb32f();
+    let pixels = img
+        .pixels()
+        .map(|p| {
+            let c = Color::new(p[0] as f64, p[1] as f64, p[2] as f64);
+            if linear {
+                c
+            } else {
+                c.srgb_to_linear()
+            }
+        })
+        .collect();
+    Ok((img.width() as usize, img.height() as usize, pixels))
+}
@@ -0,0 +1,91 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Image {
+        path: PathBuf,
+        source: image::ImageError,
+    },
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    Format {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl LoadError {
+    pub(crate) fn io(path: &Path, source: io::Error) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    pub(crate) fn image(path: &Path, source: image::ImageError) -> Self {
+        Self::Image {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    pub(crate) fn parse(path: &Path, line: usize, message: String) -> Self {
+        Self::Parse {
+            path: path.to_path_buf(),
+            line,
+            message,
+        }
+    }
+
+    pub(crate) fn format(path: &Path, message: String) -> Self {
+        Self::Format {
+            path: path.to_path_buf(),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Self::Image { path, source } => write!(f, "{}: {}", path.display(), source),
+            Self::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            Self::Format { path, message } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Image { source, .. } => Some(source),
+            Self::Parse { .. } | Self::Format { .. } => None,
+        }
+    }
+}
+
+pub mod hdr;
+pub mod obj;
+pub mod scene;
+pub mod vol;
+
+pub use hdr::*;
+pub use obj::*;
+pub use scene::*;
+pub use vol::*;
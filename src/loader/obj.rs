@@ -0,0 +1,508 @@
+use super::LoadError;
+use crate::color::Color;
+use crate::hittable::{Hittable, HittableList, TriangleMesh};
+use crate::material::{Dielectric, Diffuse, Material, Metal};
+use crate::texture::ImageTexture;
+use crate::vector3::Vector3;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub struct ObjGroup {
+    pub name: String,
+    pub material: String,
+    pub mesh: TriangleMesh,
+}
+
+pub struct ObjLoader {
+    default_material: Arc<dyn Material>,
+}
+
+impl Default for ObjLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ObjLoader {
+    pub fn new() -> Self {
+        Self {
+            default_material: Arc::new(Diffuse::new(Color::new(0.8, 0.8, 0.8))),
+        }
+    }
+
+    pub fn with_default_material(mut self, material: Arc<dyn Material>) -> Self {
+        self.default_material = material;
+        self
+    }
+
+    pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<HittableList, LoadError> {
+        Ok(self
+            .load_groups(path)?
+            .into_iter()
+            .map(|group| Box::new(group.mesh) as Box<dyn Hittable>)
+            .collect())
+    }
+
+    pub fn load_groups<P: AsRef<Path>>(&self, path: P) -> Result<Vec<ObjGroup>, LoadError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| LoadError::io(path, e))?;
+        let obj = parse_obj(path, &source)?;
+
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let mut materials = HashMap::new();
+        for lib in obj.mtllibs.iter() {
+            let path = dir.join(lib);
+            let source = fs::read_to_string(&path).map_err(|e| LoadError::io(&path, e))?;
+            materials.extend(parse_mtl(&path, &source)?);
+        }
+
+        obj.groups
+            .into_iter()
+            .filter(|group| !group.faces.is_empty())
+            .map(|group| {
+                let material = match &group.material {
+                    Some((name, line)) => materials.get(name).cloned().ok_or_else(|| {
+                        LoadError::parse(path, *line, format!("undefined material `{}`", name))
+                    })?,
+                    None => self.default_material.clone(),
+                };
+
+                Ok(ObjGroup {
+                    name: group.name,
+                    material: group.material.map(|(name, _)| name).unwrap_or_default(),
+                    mesh: build_mesh(&obj.attributes, &group.faces, material),
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct Attributes {
+    positions: Vec<Vector3>,
+    normals: Vec<Vector3>,
+    uvs: Vec<(f64, f64)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Corner {
+    position: usize,
+    uv: Option<usize>,
+    normal: Option<usize>,
+}
+
+struct Group {
+    name: String,
+    material: Option<(String, usize)>,
+    faces: Vec<[Corner; 3]>,
+}
+
+struct Obj {
+    attributes: Attributes,
+    groups: Vec<Group>,
+    mtllibs: Vec<String>,
+}
+
+fn parse_obj(path: &Path, source: &str) -> Result<Obj, LoadError> {
+    let mut attributes = Attributes::default();
+    let mut groups = vec![Group {
+        name: String::from("default"),
+        material: None,
+        faces: Vec::new(),
+    }];
+    let mut mtllibs = Vec::new();
+
+    for (i, line) in source.lines().enumerate() {
+        let line_no = i + 1;
+        let err = |message: String| LoadError::parse(path, line_no, message);
+        let mut tokens = line.split_whitespace();
+
+        let keyword = match tokens.next() {
+            Some(k) if !k.starts_with('#') => k,
+            _ => continue,
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => attributes.positions.push(parse_vector(&args).map_err(err)?),
+            "vn" => attributes.normals.push(parse_vector(&args).map_err(err)?),
+            "vt" => {
+                let uv = parse_floats(&args, 1).map_err(err)?;
+                attributes
+                    .uvs
+                    .push((uv[0], uv.get(1).copied().unwrap_or(0.0)));
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(err(String::from("a face needs at least three vertices")));
+                }
+
+                let corners = args
+                    .iter()
+                    .map(|arg| parse_corner(arg, &attributes))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(err)?;
+
+                let faces = &mut groups.last_mut().unwrap().faces;
+                for k in 1..corners.len() - 1 {
+                    faces.push([corners[0], corners[k], corners[k + 1]]);
+                }
+            }
+            "g" | "o" => {
+                let current = groups.last().unwrap();
+                let material = current.material.clone();
+                let name = if args.is_empty() {
+                    String::from("default")
+                } else {
+                    args.join(" ")
+                };
+                groups.push(Group {
+                    name,
+                    material,
+                    faces: Vec::new(),
+                });
+            }
+            "usemtl" => {
+                let name = args.join(" ");
+                if name.is_empty() {
+                    return Err(err(String::from("missing material name")));
+                }
+
+                let current = groups.last().unwrap();
+                let group_name = current.name.clone();
+                groups.push(Group {
+                    name: group_name,
+                    material: Some((name, line_no)),
+                    faces: Vec::new(),
+                });
+            }
+            "mtllib" => mtllibs.extend(args.iter().map(|s| s.to_string())),
+            _ => {}
+        }
+    }
+
+    Ok(Obj {
+        attributes,
+        groups,
+        mtllibs,
+    })
+}
+
+fn parse_floats(args: &[&str], min: usize) -> Result<Vec<f64>, String> {
+    if args.len() < min {
+        return Err(format!("expected at least {} numbers", min));
+    }
+
+    args.iter()
+        .map(|s| s.parse().map_err(|_| format!("invalid number `{}`", s)))
+        .collect()
+}
+
+fn parse_vector(args: &[&str]) -> Result<Vector3, String> {
+    let v = parse_floats(args, 3)?;
+    Ok(Vector3::new(v[0], v[1], v[2]))
+}
+
+fn parse_corner(arg: &str, attributes: &Attributes) -> Result<Corner, String> {
+    let mut parts = arg.split('/');
+
+    let index = |part: Option<&str>, len: usize| -> Result<Option<usize>, String> {
+        match part {
+            None | Some("") => Ok(None),
+            Some(s) => {
+                let i: isize = s.parse().map_err(|_| format!("invalid index `{}`", s))?;
+                let resolved = match i {
+                    0 => None,
+                    i if i > 0 => Some(i as usize - 1),
+                    i => len.checked_sub(i.unsigned_abs()),
+                };
+
+                match resolved {
+                    Some(i) if i < len => Ok(Some(i)),
+                    _ => Err(format!("index `{}` out of range", s)),
+                }
+            }
+        }
+    };
+
+    let position = index(parts.next(), attributes.positions.len())?
+        .ok_or_else(|| format!("missing vertex index in `{}`", arg))?;
+    let uv = index(parts.next(), attributes.uvs.len())?;
+    let normal = index(parts.next(), attributes.normals.len())?;
+
+    Ok(Corner {
+        position,
+        uv,
+        normal,
+    })
+}
+
+fn build_mesh(
+    attributes: &Attributes,
+    faces: &[[Corner; 3]],
+    material: Arc<dyn Material>,
+) -> TriangleMesh {
+    let corners = faces.iter().flatten();
+    let has_normals = corners.clone().all(|c| c.normal.is_some());
+    let has_uvs = corners.clone().all(|c| c.uv.is_some());
+
+    let mut remap = HashMap::new();
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+
+    let indices = faces
+        .iter()
+        .map(|face| {
+            face.map(|corner| {
+                *remap.entry(corner).or_insert_with(|| {
+                    positions.push(attributes.positions[corner.position].clone());
+                    if let (true, Some(n)) = (has_normals, corner.normal) {
+                        normals.push(attributes.normals[n].clone());
+                    }
+                    if let (true, Some(uv)) = (has_uvs, corner.uv) {
+                        uvs.push(attributes.uvs[uv]);
+                    }
+                    positions.len() - 1
+                })
+            })
+        })
+        .collect();
+
+    TriangleMesh::new(
+        positions,
+        has_normals.then_some(normals),
+        has_uvs.then_some(uvs),
+        indices,
+        material,
+    )
+}
+
+struct MtlMaterial {
+    kd: Color,
+    ks: Color,
+    ns: f64,
+    ni: f64,
+    d: f64,
+    illum: u32,
+    map_kd: Option<PathBuf>,
+}
+
+impl Default for MtlMaterial {
+    fn default() -> Self {
+        Self {
+            kd: Color::new(0.8, 0.8, 0.8),
+            ks: Color::BLACK,
+            ns: 0.0,
+            ni: 1.0,
+            d: 1.0,
+            illum: 1,
+            map_kd: None,
+        }
+    }
+}
+
+impl MtlMaterial {
+    fn build(&self) -> Result<Arc<dyn Material>, LoadError> {
+        Ok(match self.illum {
+            4 | 6 | 7 | 9 => Arc::new(Dielectric::new(self.ni)),
+            _ if self.d < 1.0 => Arc::new(Dielectric::new(self.ni)),
+            3 | 5 => {
+                let fuzz = (2.0 / (self.ns + 2.0)).sqrt();
+                Arc::new(Metal::new(self.ks.clone()).with_fuzz(fuzz))
+            }
+            _ => match &self.map_kd {
+                Some(path) => {
+                    let texture =
+                        ImageTexture::open(path).map_err(|e| LoadError::image(path, e))?;
+                    Arc::new(Diffuse::from_texture(Arc::new(texture)))
+                }
+                None => Arc::new(Diffuse::new(self.kd.clone())),
+            },
+        })
+    }
+}
+
+fn parse_mtl(path: &Path, source: &str) -> Result<HashMap<String, Arc<dyn Material>>, LoadError> {
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MtlMaterial)> = None;
+
+    for (i, line) in source.lines().enumerate() {
+        let err = |message: String| LoadError::parse(path, i + 1, message);
+        let mut tokens = line.split_whitespace();
+
+        let keyword = match tokens.next() {
+            Some(k) if !k.starts_with('#') => k,
+            _ => continue,
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            if let Some((name, mtl)) = current.take() {
+                materials.insert(name, mtl.build()?);
+            }
+
+            let name = args.join(" ");
+            if name.is_empty() {
+                return Err(err(String::from("missing material name")));
+            }
+            current = Some((name, MtlMaterial::default()));
+            continue;
+        }
+
+        let mtl = match current.as_mut() {
+            Some((_, mtl)) => mtl,
+            None if matches!(
+                keyword,
+                "Kd" | "Ks" | "Ns" | "Ni" | "d" | "Tr" | "illum" | "map_Kd"
+            ) =>
+            {
+                return Err(err(format!("`{}` before any `newmtl`", keyword)))
+            }
+            None => continue,
+        };
+
+        match keyword {
+            "Kd" => mtl.kd = parse_color(&args).map_err(err)?,
+            "Ks" => mtl.ks = parse_color(&args).map_err(err)?,
+            "Ns" => mtl.ns = parse_floats(&args, 1).map_err(err)?[0],
+            "Ni" => mtl.ni = parse_floats(&args, 1).map_err(err)?[0],
+            "d" => mtl.d = parse_floats(&args, 1).map_err(err)?[0],
+            "Tr" => mtl.d = 1.0 - parse_floats(&args, 1).map_err(err)?[0],
+            "illum" => {
+                mtl.illum = args
+                    .first()
+                    .and_then(|s| s.parse().ok())
+                    .ok_or_else(|| err(String::from("invalid illumination model")))?
+            }
+            "map_Kd" => {
+                let file = args
+                    .last()
+                    .ok_or_else(|| err(String::from("missing texture file name")))?;
+                let dir = path.parent().unwrap_or_else(|| Path::new(""));
+                mtl.map_kd = Some(dir.join(file));
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((name, mtl)) = current {
+        materials.insert(name, mtl.build()?);
+    }
+
+    Ok(materials)
+}
+
+fn parse_color(args: &[&str]) -> Result<Color, String> {
+    let c = parse_floats(args, 1)?;
+    match c[..] {
+        [v] => Ok(Color::new(v, v, v)),
+        [r, g, b, ..] => Ok(Color::new(r, g, b)),
+        _ => Err(String::from("expected one or three numbers")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const SQUARE: &str = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n";
+
+    fn positions(face: &[Corner; 3]) -> [usize; 3] {
+        face.map(|corner| corner.position)
+    }
+
+    // A directory of its own for the tests that go through the file system, removed when
+    // the test ends.
+    struct Fixture(PathBuf);
+
+    impl Fixture {
+        fn new(test: &str) -> Self {
+            let name = format!("lumo-obj-{}-{}", std::process::id(), test);
+            let dir = std::env::temp_dir().join(name);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn file(&self, name: &str, contents: &str) -> PathBuf {
+            let path = self.0.join(name);
+            fs::write(&path, contents).unwrap();
+            path
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn parse_line(err: LoadError) -> usize {
+        match err {
+            LoadError::Parse { line, .. } => line,
+            e => panic!("expected a parse error, got {e}"),
+        }
+    }
+
+    #[test]
+    fn polygons_are_triangulated_as_fans() {
+        let obj = parse_obj(Path::new("a.obj"), &format!("{SQUARE}f 1 2 3 4\n")).unwrap();
+        let faces = &obj.groups[0].faces;
+        assert_eq!(faces.len(), 2);
+        assert_eq!(positions(&faces[0]), [0, 1, 2]);
+        assert_eq!(positions(&faces[1]), [0, 2, 3]);
+    }
+
+    #[test]
+    fn negative_indices_count_back_from_the_last_vertex() {
+        let obj = parse_obj(Path::new("a.obj"), &format!("{SQUARE}f -3 -2 -1\n")).unwrap();
+        assert_eq!(positions(&obj.groups[0].faces[0]), [1, 2, 3]);
+    }
+
+    #[test]
+    fn out_of_range_indices_are_rejected() {
+        let source = format!("{SQUARE}f 1 2 5\n");
+        let err = parse_obj(Path::new("a.obj"), &source).err().unwrap();
+        assert_eq!(parse_line(err), 5);
+
+        let source = format!("{SQUARE}f -5 1 2\n");
+        assert!(parse_obj(Path::new("a.obj"), &source).is_err());
+    }
+
+    #[test]
+    fn undefined_materials_are_reported_at_usemtl() {
+        let fixture = Fixture::new("undefined");
+        let path = fixture.file("undefined.obj", &format!("{SQUARE}usemtl red\nf 1 2 3\n"));
+
+        let err = ObjLoader::new().load_groups(&path).err().unwrap();
+        assert_eq!(parse_line(err), 5);
+    }
+
+    #[test]
+    fn missing_material_libraries_are_io_errors() {
+        let fixture = Fixture::new("missing");
+        let path = fixture.file(
+            "missing.obj",
+            &format!("mtllib missing.mtl\n{SQUARE}f 1 2 3\n"),
+        );
+
+        match ObjLoader::new().load_groups(&path) {
+            Err(LoadError::Io { path, .. }) => assert!(path.ends_with("missing.mtl")),
+            Err(e) => panic!("expected an io error, got {e}"),
+            Ok(_) => panic!("expected an io error"),
+        }
+    }
+
+    #[test]
+    fn mtl_materials_are_named_by_newmtl() {
+        let source = "newmtl red\nKd 1 0 0\nnewmtl glass\nillum 7\nNi 1.5\n";
+        let materials = parse_mtl(Path::new("a.mtl"), source).unwrap();
+        assert!(materials.contains_key("red") && materials.contains_key("glass"));
+
+        let err = parse_mtl(Path::new("a.mtl"), "Kd 1 0 0\n").err().unwrap();
+        assert_eq!(parse_line(err), 1);
+    }
+}
@@ -0,0 +1,1732 @@
+use super::{load_hdr, load_vol, LoadError, ObjLoader};
+use crate::aabb::Aabb;
+use crate::background::{self, CubeMap, EnvironmentMap, Sky};
+use crate::camera::CameraBuilder;
+use crate::color::Color;
+use crate::hittable::{
+    Atmosphere, Bvh, Cone, ConstantMedium, Csg, CsgOperation, Cuboid, Cylinder, Disk,
+    DistanceField, Environment, GridMedium, Hittable, Instance, KeyframedInstance, MovingSphere,
+    Plane, Quad, Sphere, Torus, Triangle,
+};
+use crate::light::{DirectionalLight, Light, PointLight, SpotLight};
+use crate::material::{
+    Conductor, Dielectric, Diffuse, DiffuseLight, HenyeyGreenstein, Isotropic, Material, Metal,
+    Principled, RoughDielectric,
+};
+use crate::ray::Ray;
+use crate::render::Renderer;
+use crate::sdf::{self, Sdf};
+use crate::texture::{Checker, ImageTexture, NoiseTexture, SolidColor, Texture, WrapMode};
+use crate::transform::{Animation, Keyframe, Transform};
+use crate::vector3::Vector3;
+use serde::Deserialize;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use toml::Spanned;
+
+pub type Background = Box<dyn background::Background>;
+
+// A loaded mesh group and whether it is registered as a light.
+type MeshGroup = (Arc<dyn Hittable>, bool);
+
+pub struct Scene {
+    pub renderer: Renderer,
+    pub environment: Environment<Bvh, Background>,
+}
+
+impl Scene {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        SceneFile::open(path)?.build()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CameraSettings {
+    pub lookfrom: [f64; 3],
+    pub lookat: [f64; 3],
+    pub vup: [f64; 3],
+    pub fov: f64,
+    pub aspect_ratio: Option<f64>,
+    pub aperture: f64,
+    pub focus_dist: f64,
+    pub shutter: [f64; 2],
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            lookfrom: [0.0, 0.0, 0.0],
+            lookat: [0.0, 0.0, -1.0],
+            vup: [0.0, 1.0, 0.0],
+            fov: 90.0,
+            aspect_ratio: None,
+            aperture: 0.0,
+            focus_dist: 1.0,
+            shutter: [0.0, 0.0],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RenderSettings {
+    pub width: usize,
+    pub height: usize,
+    pub samples: u32,
+    pub depth: u32,
+    // Adaptive sampling stops pixels whose relative standard error falls below `target_error`
+    // after at least `min_samples`; `samples` is then the maximum.
+    pub target_error: Option<f64>,
+    pub min_samples: u32,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            samples: 128,
+            depth: 8,
+            target_error: None,
+            min_samples: 16,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneFile {
+    #[serde(default)]
+    pub camera: CameraSettings,
+    #[serde(default)]
+    pub render: RenderSettings,
+    #[serde(default)]
+    background: Option<Spanned<BackgroundConfig>>,
+    #[serde(default)]
+    atmosphere: Option<Spanned<AtmosphereConfig>>,
+    #[serde(default)]
+    lights: Vec<Spanned<LightConfig>>,
+    #[serde(default)]
+    textures: HashMap<String, Spanned<TextureConfig>>,
+    #[serde(default)]
+    materials: HashMap<String, Spanned<MaterialConfig>>,
+    #[serde(default)]
+    objects: Vec<Spanned<ObjectConfig>>,
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip)]
+    source: String,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum BackgroundConfig {
+    Solid {
+        color: [f64; 3],
+    },
+    Gradient {
+        bottom: [f64; 3],
+        top: [f64; 3],
+    },
+    // Rotations are in degrees about the y axis.
+    Equirectangular {
+        path: PathBuf,
+        rotation: Option<f64>,
+        intensity: Option<f64>,
+    },
+    // Faces in the order +x, -x, +y, -y, +z, -z.
+    CubeMap {
+        faces: [PathBuf; 6],
+        rotation: Option<f64>,
+        intensity: Option<f64>,
+    },
+    // Radiance is in kcd/m²; `sun = false` leaves out the sun disk.
+    Sky {
+        sun_direction: [f64; 3],
+        turbidity: Option<f64>,
+        ground_albedo: Option<[f64; 3]>,
+        intensity: Option<f64>,
+        sun: Option<bool>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AtmosphereConfig {
+    density: f64,
+    material: String,
+    extent: Option<f64>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum LightConfig {
+    Point {
+        position: [f64; 3],
+        intensity: [f64; 3],
+    },
+    // Cone angles are in degrees from the axis, as are the angles of `profile`, which pairs them
+    // with relative intensities.
+    Spot {
+        position: [f64; 3],
+        direction: [f64; 3],
+        intensity: [f64; 3],
+        inner_angle: Option<f64>,
+        outer_angle: Option<f64>,
+        profile: Option<Vec<[f64; 2]>>,
+    },
+    Directional {
+        direction: [f64; 3],
+        irradiance: [f64; 3],
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum WrapConfig {
+    Repeat,
+    Mirror,
+    Clamp,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum NoiseKindConfig {
+    Perlin,
+    Turbulence,
+    Marble,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum TextureConfig {
+    Solid {
+        color: [f64; 3],
+    },
+    Checker {
+        even: [f64; 3],
+        odd: [f64; 3],
+        scale: Option<f64>,
+    },
+    Image {
+        path: PathBuf,
+        wrap: Option<WrapConfig>,
+        linear: Option<bool>,
+    },
+    Noise {
+        kind: Option<NoiseKindConfig>,
+        scale: Option<f64>,
+        depth: Option<u32>,
+        color: Option<[f64; 3]>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ConductorPreset {
+    Gold,
+    Copper,
+    Aluminium,
+}
+
+// A scalar material parameter, either constant or read from a named texture.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ParamConfig {
+    Value(f64),
+    Texture(String),
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum MaterialConfig {
+    Diffuse {
+        color: Option<[f64; 3]>,
+        texture: Option<String>,
+    },
+    Metal {
+        color: Option<[f64; 3]>,
+        texture: Option<String>,
+        fuzz: Option<f64>,
+    },
+    Conductor {
+        preset: Option<ConductorPreset>,
+        eta: Option<[f64; 3]>,
+        k: Option<[f64; 3]>,
+        roughness: Option<f64>,
+    },
+    Dielectric {
+        index: f64,
+        roughness: Option<f64>,
+    },
+    Principled {
+        color: Option<[f64; 3]>,
+        texture: Option<String>,
+        metallic: Option<ParamConfig>,
+        roughness: Option<ParamConfig>,
+        specular: Option<ParamConfig>,
+        specular_tint: Option<ParamConfig>,
+        sheen: Option<ParamConfig>,
+        clearcoat: Option<ParamConfig>,
+        transmission: Option<ParamConfig>,
+        ior: Option<ParamConfig>,
+    },
+    Light {
+        color: Option<[f64; 3]>,
+        texture: Option<String>,
+        intensity: Option<f64>,
+    },
+    Isotropic {
+        color: Option<[f64; 3]>,
+        texture: Option<String>,
+    },
+    HenyeyGreenstein {
+        color: Option<[f64; 3]>,
+        texture: Option<String>,
+        g: f64,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum ObjectConfig {
+    Sphere {
+        center: [f64; 3],
+        // Moves linearly from `center` at time 0 to `end_center` at time 1.
+        end_center: Option<[f64; 3]>,
+        radius: f64,
+        material: String,
+    },
+    Plane {
+        point: [f64; 3],
+        normal: [f64; 3],
+        material: String,
+    },
+    Quad {
+        corner: [f64; 3],
+        u: [f64; 3],
+        v: [f64; 3],
+        material: String,
+    },
+    Disk {
+        center: [f64; 3],
+        normal: [f64; 3],
+        radius: f64,
+        material: String,
+    },
+    Box {
+        min: [f64; 3],
+        max: [f64; 3],
+        // About the center of the box.
+        rotate: Option<RotateConfig>,
+        material: String,
+    },
+    Cylinder {
+        base: [f64; 3],
+        top: [f64; 3],
+        radius: f64,
+        material: String,
+    },
+    Cone {
+        base: [f64; 3],
+        apex: [f64; 3],
+        radius: f64,
+        material: String,
+    },
+    Torus {
+        center: [f64; 3],
+        axis: [f64; 3],
+        major_radius: f64,
+        minor_radius: f64,
+        material: String,
+    },
+    Triangle {
+        vertices: [[f64; 3]; 3],
+        normals: Option<[[f64; 3]; 3]>,
+        uvs: Option<[[f64; 2]; 3]>,
+        material: String,
+    },
+    Mesh {
+        path: PathBuf,
+        material: Option<String>,
+        transform: Option<Vec<TransformConfig>>,
+        keyframes: Option<Vec<KeyframeConfig>>,
+    },
+    Medium {
+        boundary: BoundaryConfig,
+        density: f64,
+        material: String,
+    },
+    Volume {
+        path: PathBuf,
+        material: String,
+        density_scale: Option<f64>,
+        temperature: Option<PathBuf>,
+        intensity: Option<f64>,
+    },
+    Csg {
+        operation: OperationConfig,
+        left: SolidConfig,
+        right: SolidConfig,
+    },
+    Sdf {
+        shape: SdfConfig,
+        material: String,
+        bounds: Option<BoundsConfig>,
+        step_scale: Option<f64>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BoundsConfig {
+    min: [f64; 3],
+    max: [f64; 3],
+}
+
+// Nodes of a distance function; angles are in degrees.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum SdfConfig {
+    Sphere {
+        radius: f64,
+    },
+    RoundedBox {
+        half_extents: [f64; 3],
+        radius: Option<f64>,
+    },
+    Torus {
+        major_radius: f64,
+        minor_radius: f64,
+    },
+    Mandelbulb {
+        power: f64,
+        iterations: Option<usize>,
+    },
+    Union {
+        left: Box<SdfConfig>,
+        right: Box<SdfConfig>,
+    },
+    SmoothUnion {
+        left: Box<SdfConfig>,
+        right: Box<SdfConfig>,
+        smoothness: f64,
+    },
+    Intersection {
+        left: Box<SdfConfig>,
+        right: Box<SdfConfig>,
+    },
+    Difference {
+        left: Box<SdfConfig>,
+        right: Box<SdfConfig>,
+    },
+    Translate {
+        offset: [f64; 3],
+        shape: Box<SdfConfig>,
+    },
+    Scale {
+        factor: f64,
+        shape: Box<SdfConfig>,
+    },
+    Twist {
+        rate: f64,
+        shape: Box<SdfConfig>,
+    },
+    Repeat {
+        period: [f64; 3],
+        shape: Box<SdfConfig>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum OperationConfig {
+    Union,
+    Intersection,
+    Difference,
+}
+
+// Closed shapes that may take part in CSG.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum SolidConfig {
+    Sphere {
+        center: [f64; 3],
+        radius: f64,
+        material: String,
+    },
+    Box {
+        min: [f64; 3],
+        max: [f64; 3],
+        rotate: Option<RotateConfig>,
+        material: String,
+    },
+    Cylinder {
+        base: [f64; 3],
+        top: [f64; 3],
+        radius: f64,
+        material: String,
+    },
+    Cone {
+        base: [f64; 3],
+        apex: [f64; 3],
+        radius: f64,
+        material: String,
+    },
+    Torus {
+        center: [f64; 3],
+        axis: [f64; 3],
+        major_radius: f64,
+        minor_radius: f64,
+        material: String,
+    },
+    Mesh {
+        path: PathBuf,
+        material: String,
+    },
+    Csg {
+        operation: OperationConfig,
+        left: Box<SolidConfig>,
+        right: Box<SolidConfig>,
+    },
+}
+
+// Applied in order; angles are in degrees.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum TransformConfig {
+    Translate([f64; 3]),
+    Scale([f64; 3]),
+    Rotate { axis: [f64; 3], angle: f64 },
+}
+
+// Angles are in degrees.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeyframeConfig {
+    time: f64,
+    translate: Option<[f64; 3]>,
+    rotate: Option<RotateConfig>,
+    scale: Option<[f64; 3]>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RotateConfig {
+    axis: [f64; 3],
+    angle: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum BoundaryConfig {
+    Sphere { center: [f64; 3], radius: f64 },
+    Mesh { path: PathBuf },
+}
+
+fn vector(v: &[f64; 3]) -> Vector3 {
+    Vector3::new(v[0], v[1], v[2])
+}
+
+fn color(c: &[f64; 3]) -> Color {
+    Color::new(c[0], c[1], c[2])
+}
+
+impl SceneFile {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| LoadError::io(path, e))?;
+        Self::parse(path, source)
+    }
+
+    pub fn parse<P: AsRef<Path>>(path: P, source: String) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let mut scene: Self = toml::from_str(&source).map_err(|e| {
+            let line = e.span().map_or(0, |span| line_of(&source, span.start));
+            LoadError::parse(path, line, e.message().to_string())
+        })?;
+
+        scene.path = path.to_path_buf();
+        scene.source = source;
+        Ok(scene)
+    }
+
+    pub fn build(&self) -> Result<Scene, LoadError> {
+        let dir = self.path.parent().unwrap_or_else(|| Path::new(""));
+
+        let mut textures = HashMap::new();
+        for (name, config) in self.textures.iter() {
+            let texture = self.texture(dir, config)?;
+            textures.insert(name.as_str(), texture);
+        }
+
+        let mut materials = HashMap::new();
+        for (name, config) in self.materials.iter() {
+            let material = self.material(&textures, config)?;
+            materials.insert(name.as_str(), material);
+        }
+
+        let emissive = |name: &String| {
+            matches!(
+                self.materials.get(name).map(Spanned::get_ref),
+                Some(MaterialConfig::Light { .. })
+            )
+        };
+
+        let mut objects: Vec<Box<dyn Hittable>> = Vec::new();
+        let mut lights: Vec<Arc<dyn Hittable>> = Vec::new();
+        let mut add = |object: Arc<dyn Hittable>, light: bool| {
+            if light {
+                lights.push(object.clone());
+            }
+            objects.push(Box::new(object));
+        };
+
+        // Meshes placed several times are loaded once and instanced.
+        let mut meshes: HashMap<(&Path, Option<&str>), Vec<MeshGroup>> = HashMap::new();
+
+        for config in self.objects.iter() {
+            let material = |name: &String| {
+                materials.get(name.as_str()).cloned().ok_or_else(|| {
+                    self.error(config.span(), format!("undefined material `{}`", name))
+                })
+            };
+
+            match config.get_ref() {
+                ObjectConfig::Sphere {
+                    center,
+                    end_center: None,
+                    radius,
+                    material: name,
+                } => {
+                    let sphere = Sphere::new(vector(center), *radius, material(name)?);
+                    add(Arc::new(sphere), emissive(name));
+                }
+                ObjectConfig::Sphere {
+                    center,
+                    end_center: Some(end_center),
+                    radius,
+                    material: name,
+                } => {
+                    let sphere = MovingSphere::new(
+                        vector(center),
+                        vector(end_center),
+                        0.0,
+                        1.0,
+                        *radius,
+                        material(name)?,
+                    );
+                    add(Arc::new(sphere), false);
+                }
+                ObjectConfig::Plane {
+                    point,
+                    normal,
+                    material: name,
+                } => {
+                    let plane = Plane::new(vector(point), vector(normal), material(name)?);
+                    add(Arc::new(plane), false);
+                }
+                ObjectConfig::Quad {
+                    corner,
+                    u,
+                    v,
+                    material: name,
+                } => {
+                    let quad = Quad::new(vector(corner), vector(u), vector(v), material(name)?);
+                    add(Arc::new(quad), emissive(name));
+                }
+                ObjectConfig::Disk {
+                    center,
+                    normal,
+                    radius,
+                    material: name,
+                } => {
+                    let disk = Disk::new(vector(center), vector(normal), *radius, material(name)?);
+                    add(Arc::new(disk), emissive(name));
+                }
+                ObjectConfig::Box {
+                    min,
+                    max,
+                    rotate,
+                    material: name,
+                } => {
+                    let mut cuboid = Cuboid::new(vector(min), vector(max), material(name)?);
+                    if let Some(RotateConfig { axis, angle }) = rotate {
+                        cuboid = cuboid.with_rotation(&vector(axis), angle.to_radians());
+                    }
+                    add(Arc::new(cuboid), false);
+                }
+                ObjectConfig::Cylinder {
+                    base,
+                    top,
+                    radius,
+                    material: name,
+                } => {
+                    let cylinder =
+                        Cylinder::new(vector(base), vector(top), *radius, material(name)?);
+                    add(Arc::new(cylinder), false);
+                }
+                ObjectConfig::Cone {
+                    base,
+                    apex,
+                    radius,
+                    material: name,
+                } => {
+                    let cone = Cone::new(vector(base), vector(apex), *radius, material(name)?);
+                    add(Arc::new(cone), false);
+                }
+                ObjectConfig::Torus {
+                    center,
+                    axis,
+                    major_radius,
+                    minor_radius,
+                    material: name,
+                } => {
+                    let torus = Torus::new(
+                        vector(center),
+                        vector(axis),
+                        *major_radius,
+                        *minor_radius,
+                        material(name)?,
+                    );
+                    add(Arc::new(torus), false);
+                }
+                ObjectConfig::Triangle {
+                    vertices: [a, b, c],
+                    normals,
+                    uvs,
+                    material: name,
+                } => {
+                    let mut triangle =
+                        Triangle::new(vector(a), vector(b), vector(c), material(name)?);
+                    if let Some(normals) = normals {
+                        triangle = triangle.with_normals(normals.each_ref().map(vector));
+                    }
+                    if let Some(uvs) = uvs {
+                        triangle = triangle.with_uvs(uvs.map(|[u, v]| (u, v)));
+                    }
+                    add(Arc::new(triangle), emissive(name));
+                }
+                ObjectConfig::Mesh {
+                    path,
+                    material: name,
+                    transform,
+                    keyframes,
+                } => {
+                    let key = (path.as_path(), name.as_deref());
+                    if let Entry::Vacant(entry) = meshes.entry(key) {
+                        let mut loader = ObjLoader::new();
+                        if let Some(name) = name {
+                            loader = loader.with_default_material(material(name)?);
+                        }
+
+                        let mut groups = Vec::new();
+                        for group in loader.load_groups(dir.join(path))? {
+                            let light =
+                                group.material.is_empty() && name.as_ref().is_some_and(emissive);
+                            groups.push((Arc::new(group.mesh) as Arc<dyn Hittable>, light));
+                        }
+                        entry.insert(groups);
+                    }
+
+                    if let Some(keyframes) = keyframes {
+                        if transform.is_some() {
+                            return Err(self.error(
+                                config.span(),
+                                String::from("`transform` and `keyframes` are exclusive"),
+                            ));
+                        }
+
+                        // Moving meshes are not light-sampled.
+                        let animation = self.animation(config.span(), keyframes)?;
+                        for (mesh, _) in meshes[&key].iter() {
+                            let instance = KeyframedInstance::new(mesh.clone(), animation.clone());
+                            add(Arc::new(instance), false);
+                        }
+                        continue;
+                    }
+
+                    let transform = match transform {
+                        Some(ops) => Some(self.transform(config.span(), ops)?),
+                        None => None,
+                    };
+                    for (mesh, light) in meshes[&key].iter() {
+                        match &transform {
+                            Some(transform) => add(
+                                Arc::new(Instance::new(mesh.clone(), transform.clone())),
+                                *light,
+                            ),
+                            None => add(mesh.clone(), *light),
+                        }
+                    }
+                }
+                ObjectConfig::Medium {
+                    boundary,
+                    density,
+                    material: name,
+                } => {
+                    let phase = material(name)?;
+                    let boundary: Arc<dyn Hittable> = match boundary {
+                        BoundaryConfig::Sphere { center, radius } => {
+                            Arc::new(Sphere::new(vector(center), *radius, phase.clone()))
+                        }
+                        BoundaryConfig::Mesh { path } => Arc::new(Bvh::from(
+                            ObjLoader::new()
+                                .with_default_material(phase.clone())
+                                .load(dir.join(path))?,
+                        )),
+                    };
+                    add(
+                        Arc::new(ConstantMedium::new(boundary, *density, phase)),
+                        false,
+                    );
+                }
+                ObjectConfig::Volume {
+                    path,
+                    material: name,
+                    density_scale,
+                    temperature,
+                    intensity,
+                } => {
+                    let mut volume = GridMedium::new(load_vol(dir.join(path))?, material(name)?)
+                        .with_density_scale(density_scale.unwrap_or(1.0));
+                    if let Some(path) = temperature {
+                        volume = volume
+                            .with_temperature(load_vol(dir.join(path))?, intensity.unwrap_or(1.0));
+                    }
+                    add(Arc::new(volume), false);
+                }
+                ObjectConfig::Csg {
+                    operation,
+                    left,
+                    right,
+                } => {
+                    let csg = self.csg(dir, config.span(), &materials, operation, left, right)?;
+                    add(csg, false);
+                }
+                ObjectConfig::Sdf {
+                    shape,
+                    material: name,
+                    bounds,
+                    step_scale,
+                } => {
+                    let step_scale = step_scale.unwrap_or(1.0);
+                    if step_scale <= 0.0 {
+                        return Err(self
+                            .error(config.span(), String::from("`step_scale` must be positive")));
+                    }
+                    let shape = self.sdf(config.span(), shape)?;
+                    let mut field =
+                        DistanceField::new(shape, material(name)?).with_step_scale(step_scale);
+                    if let Some(BoundsConfig { min, max }) = bounds {
+                        field = field.with_bounds(Aabb::new(vector(min), vector(max)));
+                    }
+                    add(Arc::new(field), false);
+                }
+            }
+        }
+
+        let settings = &self.render;
+        let camera = &self.camera;
+        let aspect_ratio = camera
+            .aspect_ratio
+            .unwrap_or(settings.width as f64 / settings.height as f64);
+        let camera = CameraBuilder::new()
+            .with_lookfrom(vector(&camera.lookfrom))
+            .with_lookat(vector(&camera.lookat))
+            .with_vup(vector(&camera.vup))
+            .with_fov(camera.fov.to_radians())
+            .with_aspect_ratio(aspect_ratio)
+            .with_aperture(camera.aperture)
+            .with_focus_dist(camera.focus_dist)
+            .with_shutter(camera.shutter[0], camera.shutter[1])
+            .build();
+
+        let mut renderer = Renderer::new(
+            settings.width,
+            settings.height,
+            settings.samples,
+            settings.depth,
+            camera,
+        );
+        if let Some(target_error) = settings.target_error {
+            if target_error.is_nan() || target_error <= 0.0 {
+                return Err(LoadError::format(
+                    &self.path,
+                    String::from("`target_error` must be positive"),
+                ));
+            }
+            renderer = renderer.with_adaptive_sampling(settings.min_samples.max(1), target_error);
+        }
+
+        let background: Background = match self.background.as_ref().map(Spanned::get_ref) {
+            Some(BackgroundConfig::Solid { color: c }) => {
+                let c = color(c);
+                Box::new(move |_: &Ray| c.clone())
+            }
+            Some(BackgroundConfig::Gradient { bottom, top }) => {
+                let (bottom, top) = (color(bottom), color(top));
+                Box::new(move |r: &Ray| {
+                    let t = 0.5 * (r.direction.normalized().y + 1.0);
+                    bottom.lerp(&top, t)
+                })
+            }
+            Some(BackgroundConfig::Equirectangular {
+                path,
+                rotation,
+                intensity,
+            }) => {
+                let (width, height, pixels) = load_hdr(dir.join(path))?;
+                Box::new(
+                    EnvironmentMap::new(width, height, pixels)
+                        .with_rotation(rotation.unwrap_or(0.0).to_radians())
+                        .with_intensity(intensity.unwrap_or(1.0)),
+                )
+            }
+            Some(BackgroundConfig::CubeMap {
+                faces,
+                rotation,
+                intensity,
+            }) => {
+                let mut size = None;
+                let mut pixels = Vec::new();
+                for path in faces {
+                    let path = dir.join(path);
+                    let (width, height, face) = load_hdr(&path)?;
+                    if width != height || size.is_some_and(|size| size != width) {
+                        return Err(LoadError::format(
+                            &path,
+                            String::from("cube map faces must be square and of equal size"),
+                        ));
+                    }
+                    size = Some(width);
+                    pixels.push(face);
+                }
+
+                let faces: [Vec<Color>; 6] = pixels.try_into().unwrap();
+                Box::new(
+                    CubeMap::new(size.unwrap(), faces)
+                        .with_rotation(rotation.unwrap_or(0.0).to_radians())
+                        .with_intensity(intensity.unwrap_or(1.0)),
+                )
+            }
+            Some(BackgroundConfig::Sky {
+                sun_direction,
+                turbidity,
+                ground_albedo,
+                intensity,
+                sun,
+            }) => {
+                let span = self.background.as_ref().unwrap().span();
+                let sun_direction = vector(sun_direction);
+                if sun_direction.is_nearly_zero() {
+                    return Err(self.error(span, String::from("`sun_direction` must be non-zero")));
+                }
+
+                let turbidity = turbidity.unwrap_or(2.5);
+                if !(1.7..=10.0).contains(&turbidity) {
+                    return Err(
+                        self.error(span, String::from("`turbidity` must be between 1.7 and 10"))
+                    );
+                }
+
+                let mut sky = Sky::new(sun_direction)
+                    .with_turbidity(turbidity)
+                    .with_intensity(intensity.unwrap_or(1.0));
+                if let Some(albedo) = ground_albedo {
+                    sky = sky.with_ground_albedo(color(albedo));
+                }
+                if *sun == Some(false) {
+                    sky = sky.without_sun();
+                }
+                Box::new(sky)
+            }
+            None => Box::new(|r: &Ray| {
+                let t = 0.5 * (r.direction.normalized().y + 1.0);
+                Color::WHITE.lerp(&Color::new(0.5, 0.7, 1.0), t)
+            }),
+        };
+
+        let punctual_lights = self
+            .lights
+            .iter()
+            .map(|config| self.light(config))
+            .collect::<Result<_, _>>()?;
+
+        let mut environment = Environment::new(Bvh::new(objects), background)
+            .with_lights(lights)
+            .with_punctual_lights(punctual_lights);
+        if let Some(config) = &self.atmosphere {
+            let AtmosphereConfig {
+                density,
+                material: name,
+                extent,
+            } = config.get_ref();
+            let phase = materials.get(name.as_str()).cloned().ok_or_else(|| {
+                self.error(config.span(), format!("undefined material `{}`", name))
+            })?;
+
+            let mut atmosphere = Atmosphere::new(*density, phase);
+            if let Some(extent) = extent {
+                atmosphere = atmosphere.with_extent(*extent);
+            }
+            environment = environment.with_atmosphere(atmosphere);
+        }
+
+        Ok(Scene {
+            renderer,
+            environment,
+        })
+    }
+
+    fn light(&self, config: &Spanned<LightConfig>) -> Result<Arc<dyn Light>, LoadError> {
+        let direction = |d: &[f64; 3]| {
+            let d = vector(d);
+            if d.is_nearly_zero() {
+                Err(self.error(config.span(), String::from("`direction` must be non-zero")))
+            } else {
+                Ok(d)
+            }
+        };
+
+        Ok(match config.get_ref() {
+            LightConfig::Point {
+                position,
+                intensity,
+            } => Arc::new(PointLight::new(vector(position), color(intensity))),
+            LightConfig::Spot {
+                position,
+                direction: axis,
+                intensity,
+                inner_angle,
+                outer_angle,
+                profile,
+            } => {
+                let inner = inner_angle.unwrap_or(30.0);
+                let outer = outer_angle.unwrap_or(inner.max(45.0));
+                if !(0.0 <= inner && inner <= outer && outer <= 180.0) {
+                    return Err(self.error(
+                        config.span(),
+                        String::from("cone angles must satisfy 0 <= inner <= outer <= 180"),
+                    ));
+                }
+
+                let mut light =
+                    SpotLight::new(vector(position), direction(axis)?, color(intensity))
+                        .with_cone(inner.to_radians(), outer.to_radians());
+                if let Some(profile) = profile {
+                    if profile.is_empty() || profile.windows(2).any(|w| w[0][0] >= w[1][0]) {
+                        return Err(self.error(
+                            config.span(),
+                            String::from("`profile` angles must be non-empty and increasing"),
+                        ));
+                    }
+                    let profile = profile.iter().map(|[a, v]| (a.to_radians(), *v)).collect();
+                    light = light.with_profile(profile);
+                }
+                Arc::new(light)
+            }
+            LightConfig::Directional {
+                direction: d,
+                irradiance,
+            } => Arc::new(DirectionalLight::new(direction(d)?, color(irradiance))),
+        })
+    }
+
+    fn texture(
+        &self,
+        dir: &Path,
+        config: &Spanned<TextureConfig>,
+    ) -> Result<Arc<dyn Texture>, LoadError> {
+        Ok(match config.get_ref() {
+            TextureConfig::Solid { color: c } => Arc::new(SolidColor::new(color(c))),
+            TextureConfig::Checker { even, odd, scale } => Arc::new(Checker::from_colors(
+                color(even),
+                color(odd),
+                scale.unwrap_or(1.0),
+            )),
+            TextureConfig::Image { path, wrap, linear } => {
+                let path = dir.join(path);
+                let wrap = match wrap {
+                    Some(WrapConfig::Repeat) | None => WrapMode::Repeat,
+                    Some(WrapConfig::Mirror) => WrapMode::Mirror,
+                    Some(WrapConfig::Clamp) => WrapMode::Clamp,
+                };
+                let texture = if linear.unwrap_or(false) {
+                    ImageTexture::open_linear(&path)
+                } else {
+                    ImageTexture::open(&path)
+                }
+                .map_err(|e| LoadError::image(&path, e))?;
+                Arc::new(texture.with_wrap(wrap))
+            }
+            TextureConfig::Noise {
+                kind,
+                scale,
+                depth,
+                color: c,
+            } => {
+                let (scale, depth) = (scale.unwrap_or(1.0), depth.unwrap_or(7));
+                let noise = match kind {
+                    Some(NoiseKindConfig::Perlin) | None => NoiseTexture::perlin(scale),
+                    Some(NoiseKindConfig::Turbulence) => NoiseTexture::turbulence(scale, depth),
+                    Some(NoiseKindConfig::Marble) => NoiseTexture::marble(scale, depth),
+                };
+                Arc::new(noise.with_color(c.as_ref().map_or(Color::WHITE, color)))
+            }
+        })
+    }
+
+    fn material(
+        &self,
+        textures: &HashMap<&str, Arc<dyn Texture>>,
+        config: &Spanned<MaterialConfig>,
+    ) -> Result<Arc<dyn Material>, LoadError> {
+        let texture = |name: &String| {
+            textures
+                .get(name.as_str())
+                .cloned()
+                .ok_or_else(|| self.error(config.span(), format!("undefined texture `{}`", name)))
+        };
+        let albedo = |c: &Option<[f64; 3]>, name: &Option<String>| match (c, name) {
+            (Some(c), None) => Ok(Arc::new(SolidColor::new(color(c))) as Arc<dyn Texture>),
+            (None, Some(name)) => texture(name),
+            (Some(_), Some(_)) => Err(self.error(
+                config.span(),
+                String::from("`color` and `texture` are mutually exclusive"),
+            )),
+            (None, None) => Err(self.error(
+                config.span(),
+                String::from("either `color` or `texture` is required"),
+            )),
+        };
+
+        Ok(match config.get_ref() {
+            MaterialConfig::Diffuse { color, texture } => {
+                Arc::new(Diffuse::from_texture(albedo(color, texture)?))
+            }
+            MaterialConfig::Metal {
+                color,
+                texture,
+                fuzz,
+            } => Arc::new(
+                Metal::from_texture(albedo(color, texture)?).with_fuzz(fuzz.unwrap_or(0.0)),
+            ),
+            MaterialConfig::Conductor {
+                preset,
+                eta,
+                k,
+                roughness,
+            } => {
+                let conductor = match (preset, eta, k) {
+                    (Some(ConductorPreset::Gold), None, None) => Conductor::gold(),
+                    (Some(ConductorPreset::Copper), None, None) => Conductor::copper(),
+                    (Some(ConductorPreset::Aluminium), None, None) => Conductor::aluminium(),
+                    (None, Some(eta), Some(k)) => Conductor::new(color(eta), color(k)),
+                    _ => {
+                        return Err(self.error(
+                            config.span(),
+                            String::from("either `preset` or both `eta` and `k` are required"),
+                        ))
+                    }
+                };
+                Arc::new(conductor.with_roughness(roughness.unwrap_or(0.0)))
+            }
+            MaterialConfig::Dielectric { index, roughness } => match roughness {
+                Some(roughness) => {
+                    Arc::new(RoughDielectric::new(*index).with_roughness(*roughness))
+                }
+                None => Arc::new(Dielectric::new(*index)),
+            },
+            MaterialConfig::Principled {
+                color,
+                texture: name,
+                metallic,
+                roughness,
+                specular,
+                specular_tint,
+                sheen,
+                clearcoat,
+                transmission,
+                ior,
+            } => {
+                let param = |param: &Option<ParamConfig>| match param {
+                    Some(ParamConfig::Value(v)) => Ok(Some(Arc::new(SolidColor::new(Color::new(
+                        *v, *v, *v,
+                    )))
+                        as Arc<dyn Texture>)),
+                    Some(ParamConfig::Texture(name)) => texture(name).map(Some),
+                    None => Ok(None),
+                };
+
+                let mut material = Principled::from_texture(albedo(color, name)?);
+                if let Some(map) = param(metallic)? {
+                    material = material.with_metallic_map(map);
+                }
+                if let Some(map) = param(roughness)? {
+                    material = material.with_roughness_map(map);
+                }
+                if let Some(map) = param(specular)? {
+                    material = material.with_specular_map(map);
+                }
+                if let Some(map) = param(specular_tint)? {
+                    material = material.with_specular_tint_map(map);
+                }
+                if let Some(map) = param(sheen)? {
+                    material = material.with_sheen_map(map);
+                }
+                if let Some(map) = param(clearcoat)? {
+                    material = material.with_clearcoat_map(map);
+                }
+                if let Some(map) = param(transmission)? {
+                    material = material.with_transmission_map(map);
+                }
+                if let Some(map) = param(ior)? {
+                    material = material.with_ior_map(map);
+                }
+                Arc::new(material)
+            }
+            MaterialConfig::Isotropic { color, texture } => {
+                Arc::new(Isotropic::from_texture(albedo(color, texture)?))
+            }
+            MaterialConfig::HenyeyGreenstein { color, texture, g } => {
+                Arc::new(HenyeyGreenstein::from_texture(albedo(color, texture)?, *g))
+            }
+            MaterialConfig::Light {
+                color,
+                texture,
+                intensity,
+            } => Arc::new(
+                DiffuseLight::from_texture(albedo(color, texture)?)
+                    .with_intensity(intensity.unwrap_or(1.0)),
+            ),
+        })
+    }
+
+    fn transform(
+        &self,
+        span: Range<usize>,
+        ops: &[TransformConfig],
+    ) -> Result<Transform, LoadError> {
+        ops.iter().try_fold(Transform::identity(), |acc, op| {
+            let next = match op {
+                TransformConfig::Translate(t) => Transform::translate(&vector(t)),
+                TransformConfig::Scale(s) if s.iter().all(|&v| v != 0.0) => {
+                    Transform::scale(&vector(s))
+                }
+                TransformConfig::Scale(_) => {
+                    return Err(self.error(span.clone(), String::from("scale must be non-zero")))
+                }
+                TransformConfig::Rotate { axis, angle } => {
+                    Transform::rotate(&vector(axis), angle.to_radians())
+                }
+            };
+            Ok(acc.then(&next))
+        })
+    }
+
+    fn csg(
+        &self,
+        dir: &Path,
+        span: Range<usize>,
+        materials: &HashMap<&str, Arc<dyn Material>>,
+        operation: &OperationConfig,
+        left: &SolidConfig,
+        right: &SolidConfig,
+    ) -> Result<Arc<dyn Hittable>, LoadError> {
+        let operation = match operation {
+            OperationConfig::Union => CsgOperation::Union,
+            OperationConfig::Intersection => CsgOperation::Intersection,
+            OperationConfig::Difference => CsgOperation::Difference,
+        };
+        let left = self.solid(dir, span.clone(), materials, left)?;
+        let right = self.solid(dir, span, materials, right)?;
+        Ok(Arc::new(Csg::new(operation, left, right)))
+    }
+
+    fn solid(
+        &self,
+        dir: &Path,
+        span: Range<usize>,
+        materials: &HashMap<&str, Arc<dyn Material>>,
+        config: &SolidConfig,
+    ) -> Result<Arc<dyn Hittable>, LoadError> {
+        let material = |name: &String| {
+            materials
+                .get(name.as_str())
+                .cloned()
+                .ok_or_else(|| self.error(span.clone(), format!("undefined material `{}`", name)))
+        };
+
+        Ok(match config {
+            SolidConfig::Sphere {
+                center,
+                radius,
+                material: name,
+            } => Arc::new(Sphere::new(vector(center), *radius, material(name)?)),
+            SolidConfig::Box {
+                min,
+                max,
+                rotate,
+                material: name,
+            } => {
+                let mut cuboid = Cuboid::new(vector(min), vector(max), material(name)?);
+                if let Some(RotateConfig { axis, angle }) = rotate {
+                    cuboid = cuboid.with_rotation(&vector(axis), angle.to_radians());
+                }
+                Arc::new(cuboid)
+            }
+            SolidConfig::Cylinder {
+                base,
+                top,
+                radius,
+                material: name,
+            } => Arc::new(Cylinder::new(
+                vector(base),
+                vector(top),
+                *radius,
+                material(name)?,
+            )),
+            SolidConfig::Cone {
+                base,
+                apex,
+                radius,
+                material: name,
+            } => Arc::new(Cone::new(
+                vector(base),
+                vector(apex),
+                *radius,
+                material(name)?,
+            )),
+            SolidConfig::Torus {
+                center,
+                axis,
+                major_radius,
+                minor_radius,
+                material: name,
+            } => Arc::new(Torus::new(
+                vector(center),
+                vector(axis),
+                *major_radius,
+                *minor_radius,
+                material(name)?,
+            )),
+            SolidConfig::Mesh {
+                path,
+                material: name,
+            } => Arc::new(Bvh::from(
+                ObjLoader::new()
+                    .with_default_material(material(name)?)
+                    .load(dir.join(path))?,
+            )),
+            SolidConfig::Csg {
+                operation,
+                left,
+                right,
+            } => self.csg(dir, span, materials, operation, left, right)?,
+        })
+    }
+
+    fn sdf(&self, span: Range<usize>, config: &SdfConfig) -> Result<Arc<dyn Sdf>, LoadError> {
+        let node = |config| self.sdf(span.clone(), config);
+
+        Ok(match config {
+            SdfConfig::Sphere { radius } => Arc::new(sdf::Sphere::new(*radius)),
+            SdfConfig::RoundedBox {
+                half_extents,
+                radius,
+            } => Arc::new(sdf::RoundedBox::new(
+                vector(half_extents),
+                radius.unwrap_or(0.0),
+            )),
+            SdfConfig::Torus {
+                major_radius,
+                minor_radius,
+            } => Arc::new(sdf::Torus::new(*major_radius, *minor_radius)),
+            SdfConfig::Mandelbulb { power, iterations } => {
+                let mut bulb = sdf::Mandelbulb::new(*power);
+                if let Some(iterations) = iterations {
+                    bulb = bulb.with_iterations(*iterations);
+                }
+                Arc::new(bulb)
+            }
+            SdfConfig::Union { left, right } => Arc::new(node(left)?.union(node(right)?)),
+            SdfConfig::SmoothUnion {
+                left,
+                right,
+                smoothness,
+            } => {
+                if *smoothness < 0.0 {
+                    return Err(self.error(span, String::from("`smoothness` must not be negative")));
+                }
+                Arc::new(node(left)?.smooth_union(node(right)?, *smoothness))
+            }
+            SdfConfig::Intersection { left, right } => {
+                Arc::new(node(left)?.intersection(node(right)?))
+            }
+            SdfConfig::Difference { left, right } => Arc::new(node(left)?.difference(node(right)?)),
+            SdfConfig::Translate { offset, shape } => {
+                Arc::new(node(shape)?.translate(vector(offset)))
+            }
+            SdfConfig::Scale { factor, shape } => {
+                if *factor <= 0.0 {
+                    return Err(self.error(span, String::from("scale `factor` must be positive")));
+                }
+                Arc::new(node(shape)?.scale(*factor))
+            }
+            SdfConfig::Twist { rate, shape } => Arc::new(node(shape)?.twist(rate.to_radians())),
+            SdfConfig::Repeat { period, shape } => Arc::new(node(shape)?.repeat(vector(period))),
+        })
+    }
+
+    fn animation(
+        &self,
+        span: Range<usize>,
+        keyframes: &[KeyframeConfig],
+    ) -> Result<Animation, LoadError> {
+        if keyframes.is_empty() {
+            return Err(self.error(span, String::from("`keyframes` must not be empty")));
+        }
+
+        let mut frames = Vec::new();
+        for config in keyframes {
+            let mut frame = Keyframe::new(config.time);
+            if let Some(t) = &config.translate {
+                frame = frame.with_translation(vector(t));
+            }
+            if let Some(RotateConfig { axis, angle }) = &config.rotate {
+                frame = frame.with_rotation(&vector(axis), angle.to_radians());
+            }
+            if let Some(s) = &config.scale {
+                if s.contains(&0.0) {
+                    return Err(self.error(span, String::from("scale must be non-zero")));
+                }
+                frame = frame.with_scale(vector(s));
+            }
+            frames.push(frame);
+        }
+
+        if !Animation::is_valid(&frames) {
+            return Err(self.error(
+                span,
+                String::from("scale must not change sign between keyframes"),
+            ));
+        }
+
+        Ok(Animation::new(frames))
+    }
+
+    fn error(&self, span: Range<usize>, message: String) -> LoadError {
+        LoadError::parse(&self.path, line_of(&self.source, span.start), message)
+    }
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())]
+        .bytes()
+        .filter(|&b| b == b'\n')
+        .count()
+        + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENE: &str = r#"
+[render]
+width = 4
+height = 2
+samples = 3
+
+[materials.red]
+type = "diffuse"
+color = [1.0, 0.0, 0.0]
+
+[[objects]]
+type = "sphere"
+center = [0.0, 0.0, -1.0]
+radius = 0.5
+material = "red"
+"#;
+
+    fn parse(source: &str) -> Result<SceneFile, LoadError> {
+        SceneFile::parse("scene.toml", source.to_string())
+    }
+
+    fn parse_error(result: Result<impl Sized, LoadError>) -> (usize, String) {
+        match result {
+            Err(LoadError::Parse { line, message, .. }) => (line, message),
+            Err(e) => panic!("expected a parse error, got {e}"),
+            Ok(_) => panic!("expected a parse error"),
+        }
+    }
+
+    fn build_error(source: &str) -> (usize, String) {
+        parse_error(parse(source).unwrap().build())
+    }
+
+    #[test]
+    fn settings_are_read_and_defaulted() {
+        let file = parse(SCENE).unwrap();
+        assert_eq!((file.render.width, file.render.samples), (4, 3));
+        assert_eq!(file.render.depth, RenderSettings::default().depth);
+        assert!(file.build().is_ok());
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let source = SCENE.replace("samples = 3", "samples = 3\nspp = 3");
+        let (line, message) = parse_error(parse(&source));
+        assert_eq!(line, 6);
+        assert!(message.contains("spp"), "{message}");
+    }
+
+    #[test]
+    fn undefined_materials_are_reported_at_the_object() {
+        let source = SCENE.replace("material = \"red\"", "material = \"blue\"");
+        let (line, message) = build_error(&source);
+        assert_eq!(line, 11);
+        assert_eq!(message, "undefined material `blue`");
+    }
+
+    #[test]
+    fn albedos_need_exactly_one_source() {
+        let source = SCENE.replace("color = [1.0, 0.0, 0.0]", "");
+        let (line, message) = build_error(&source);
+        assert_eq!(line, 7);
+        assert_eq!(message, "either `color` or `texture` is required");
+
+        let source = SCENE.replace(
+            "color = [1.0, 0.0, 0.0]",
+            "color = [1.0, 0.0, 0.0]\ntexture = \"t\"",
+        );
+        let (_, message) = build_error(&source);
+        assert_eq!(message, "`color` and `texture` are mutually exclusive");
+
+        let source = SCENE.replace("color = [1.0, 0.0, 0.0]", "texture = \"wood\"");
+        let (_, message) = build_error(&source);
+        assert_eq!(message, "undefined texture `wood`");
+    }
+
+    #[test]
+    fn conductors_need_a_preset_or_optical_constants() {
+        let source = SCENE.replace(
+            "type = \"diffuse\"\ncolor = [1.0, 0.0, 0.0]",
+            "type = \"conductor\"\npreset = \"gold\"\neta = [0.2, 0.4, 1.4]",
+        );
+        let (line, message) = build_error(&source);
+        assert_eq!(line, 7);
+        assert_eq!(
+            message,
+            "either `preset` or both `eta` and `k` are required"
+        );
+    }
+
+    #[test]
+    fn transforms_reject_zero_scale() {
+        let file = parse(SCENE).unwrap();
+        let ops = [
+            TransformConfig::Translate([1.0, 0.0, 0.0]),
+            TransformConfig::Scale([2.0, 0.0, 2.0]),
+        ];
+        let (_, message) = parse_error(file.transform(0..0, &ops));
+        assert_eq!(message, "scale must be non-zero");
+        assert!(file.transform(0..0, &ops[..1]).is_ok());
+    }
+
+    // A scene file next to the files it refers to, in a directory removed when the test ends.
+    struct Fixture(PathBuf);
+
+    impl Fixture {
+        fn new(test: &str, files: &[(&str, &str)]) -> Self {
+            let name = format!("lumo-scene-{}-{}", std::process::id(), test);
+            let dir = std::env::temp_dir().join(name);
+            fs::create_dir_all(&dir).unwrap();
+            for (name, contents) in files {
+                fs::write(dir.join(name), contents).unwrap();
+            }
+            Self(dir)
+        }
+
+        fn parse(&self, source: &str) -> SceneFile {
+            SceneFile::parse(self.0.join("scene.toml"), source.to_string()).unwrap()
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn meshes_take_a_transform_or_keyframes() {
+        let fixture = Fixture::new(
+            "keyframes",
+            &[("tri.obj", "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n")],
+        );
+        let mesh = r#"
+[[objects]]
+type = "mesh"
+path = "tri.obj"
+material = "red"
+keyframes = [{ time = 0.0 }, { time = 1.0, translate = [1.0, 0.0, 0.0] }]
+"#;
+        assert!(fixture.parse(&format!("{SCENE}{mesh}")).build().is_ok());
+
+        let mesh = mesh.replace(
+            "keyframes",
+            "transform = [{ scale = [2.0, 2.0, 2.0] }]\nkeyframes",
+        );
+        let (line, message) = parse_error(fixture.parse(&format!("{SCENE}{mesh}")).build());
+        assert_eq!(line, 17);
+        assert_eq!(message, "`transform` and `keyframes` are exclusive");
+    }
+
+    #[test]
+    fn keyframes_keep_a_nonzero_scale_of_fixed_sign() {
+        let file = parse(SCENE).unwrap();
+        let frame = |time: f64, scale: [f64; 3]| KeyframeConfig {
+            time,
+            translate: None,
+            rotate: None,
+            scale: Some(scale),
+        };
+
+        let (_, message) = parse_error(file.animation(0..0, &[]));
+        assert_eq!(message, "`keyframes` must not be empty");
+
+        let (_, message) = parse_error(file.animation(0..0, &[frame(0.0, [1.0, 0.0, 1.0])]));
+        assert_eq!(message, "scale must be non-zero");
+
+        let frames = [frame(0.0, [1.0, 1.0, 1.0]), frame(1.0, [1.0, -1.0, 1.0])];
+        let (_, message) = parse_error(file.animation(0..0, &frames));
+        assert_eq!(message, "scale must not change sign between keyframes");
+        assert!(file.animation(0..0, &frames[..1]).is_ok());
+    }
+
+    #[test]
+    fn cube_map_faces_must_be_square_and_equal() {
+        let fixture = Fixture::new("cube-map", &[]);
+        for (name, size) in [("a.png", 2), ("b.png", 4)] {
+            image::RgbImage::new(size, size)
+                .save(fixture.0.join(name))
+                .unwrap();
+        }
+        let background = |last: &str| {
+            format!(
+                "{SCENE}\n[background]\ntype = \"cube_map\"\n\
+                 faces = [\"a.png\", \"a.png\", \"a.png\", \"a.png\", \"a.png\", \"{last}\"]\n"
+            )
+        };
+        assert!(fixture.parse(&background("a.png")).build().is_ok());
+
+        match fixture.parse(&background("b.png")).build() {
+            Err(LoadError::Format { path, message }) => {
+                assert_eq!(path, fixture.0.join("b.png"));
+                assert_eq!(message, "cube map faces must be square and of equal size");
+            }
+            Err(e) => panic!("expected a format error, got {e}"),
+            Ok(_) => panic!("expected a format error"),
+        }
+    }
+
+    #[test]
+    fn skies_need_a_sun_direction_and_plausible_turbidity() {
+        let sky = |fields: &str| format!("{SCENE}\n[background]\ntype = \"sky\"\n{fields}\n");
+        assert!(parse(&sky("sun_direction = [0.0, 1.0, 1.0]"))
+            .unwrap()
+            .build()
+            .is_ok());
+
+        let (line, message) = build_error(&sky("sun_direction = [0.0, 0.0, 0.0]"));
+        assert_eq!(line, 17);
+        assert_eq!(message, "`sun_direction` must be non-zero");
+
+        let source = sky("sun_direction = [0.0, 1.0, 1.0]\nturbidity = 20.0");
+        let (_, message) = build_error(&source);
+        assert_eq!(message, "`turbidity` must be between 1.7 and 10");
+    }
+
+    #[test]
+    fn spot_lights_need_an_axis_ordered_cone_and_increasing_profile() {
+        let light = |fields: &str| {
+            format!(
+                "{SCENE}\n[[lights]]\ntype = \"spot\"\nposition = [0.0, 2.0, 0.0]\n\
+                 intensity = [10.0, 10.0, 10.0]\n{fields}\n"
+            )
+        };
+        let source = light("direction = [0.0, -1.0, 0.0]\ninner_angle = 20.0\nouter_angle = 40.0");
+        assert!(parse(&source).unwrap().build().is_ok());
+
+        let (line, message) = build_error(&light("direction = [0.0, 0.0, 0.0]"));
+        assert_eq!(line, 17);
+        assert_eq!(message, "`direction` must be non-zero");
+
+        let source = light("direction = [0.0, -1.0, 0.0]\ninner_angle = 40.0\nouter_angle = 20.0");
+        let (_, message) = build_error(&source);
+        assert_eq!(
+            message,
+            "cone angles must satisfy 0 <= inner <= outer <= 180"
+        );
+
+        let source =
+            light("direction = [0.0, -1.0, 0.0]\nprofile = [[0.0, 1.0], [20.0, 0.5], [20.0, 0.0]]");
+        let (_, message) = build_error(&source);
+        assert_eq!(message, "`profile` angles must be non-empty and increasing");
+    }
+
+    #[test]
+    fn target_errors_must_be_positive() {
+        let source = SCENE.replace("samples = 3", "samples = 3\ntarget_error = 0.05");
+        assert!(parse(&source).unwrap().build().is_ok());
+
+        for value in ["0.0", "-0.1", "nan"] {
+            let source = SCENE.replace(
+                "samples = 3",
+                &format!("samples = 3\ntarget_error = {value}"),
+            );
+            match parse(&source).unwrap().build() {
+                Err(LoadError::Format { message, .. }) => {
+                    assert_eq!(message, "`target_error` must be positive")
+                }
+                Err(e) => panic!("expected a format error for {value}, got {e}"),
+                Ok(_) => panic!("expected a format error for {value}"),
+            }
+        }
+    }
+
+    #[test]
+    fn distance_fields_reject_degenerate_parameters() {
+        let field = |shape: &str, extra: &str| {
+            format!(
+                "{SCENE}\n[[objects]]\ntype = \"sdf\"\nmaterial = \"red\"\n{extra}\nshape = {shape}\n"
+            )
+        };
+        let sphere = "{ type = \"sphere\", radius = 1.0 }";
+        let smooth = |k: f64| {
+            format!("{{ type = \"smooth_union\", smoothness = {k:?}, left = {sphere}, right = {sphere} }}")
+        };
+        assert!(parse(&field(&smooth(0.2), "")).unwrap().build().is_ok());
+
+        let scaled = format!(
+            "{{ type = \"scale\", factor = 0.0, shape = {} }}",
+            smooth(0.2)
+        );
+        let (line, message) = build_error(&field(&scaled, ""));
+        assert_eq!(line, 17);
+        assert_eq!(message, "scale `factor` must be positive");
+
+        let (_, message) = build_error(&field(&smooth(-0.1), ""));
+        assert_eq!(message, "`smoothness` must not be negative");
+
+        let (_, message) = build_error(&field(sphere, "step_scale = 0.0"));
+        assert_eq!(message, "`step_scale` must be positive");
+    }
+}
@@ -0,0 +1,143 @@
+use super::LoadError;
+use crate::aabb::Aabb;
+use crate::hittable::VoxelGrid;
+use crate::vector3::Vector3;
+use std::fs;
+use std::path::Path;
+
+// Reads a Mitsuba style `.vol` grid: the bytes `VOL` and version 3, then little-endian i32
+// encoding (1 = f32, 3 = u8), x/y/z resolution and channel count, six f32 bounds (min then max)
+// and the voxels, x-fastest with interleaved channels. Only the first channel is kept.
+pub fn load_vol<P: AsRef<Path>>(path: P) -> Result<VoxelGrid, LoadError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|e| LoadError::io(path, e))?;
+    parse_vol(path, &bytes)
+}
+
+fn parse_vol(path: &Path, bytes: &[u8]) -> Result<VoxelGrid, LoadError> {
+    let err = |message: &str| LoadError::format(path, message.to_string());
+
+    if bytes.len() < 48 || &bytes[..3] != b"VOL" {
+        return Err(err("not a .vol file"));
+    }
+    if bytes[3] != 3 {
+        return Err(err("unsupported .vol version"));
+    }
+
+    let word = |i: usize| -> [u8; 4] { bytes[4 + 4 * i..8 + 4 * i].try_into().unwrap() };
+    let int = |i: usize| i32::from_le_bytes(word(i));
+    let float = |i: usize| f32::from_le_bytes(word(i)) as f64;
+
+    let encoding = int(0);
+    let resolution = [int(1), int(2), int(3)];
+    let channels = int(4);
+    if resolution.iter().any(|&n| n <= 0) || channels <= 0 {
+        return Err(err("invalid grid dimensions"));
+    }
+
+    let resolution = resolution.map(|n| n as usize);
+    let channels = channels as usize;
+    let bounds = Aabb::new(
+        Vector3::new(float(5), float(6), float(7)),
+        Vector3::new(float(8), float(9), float(10)),
+    );
+
+    let value_size = match encoding {
+        1 => 4,
+        3 => 1,
+        _ => return Err(err("unsupported .vol encoding")),
+    };
+    // Headers can claim grids far larger than memory; reject sizes that overflow.
+    let count = resolution
+        .iter()
+        .try_fold(1usize, |count, &n| count.checked_mul(n));
+    let size = count.and_then(|count| count.checked_mul(channels)?.checked_mul(value_size));
+    let (Some(count), Some(size)) = (count, size) else {
+        return Err(err("invalid grid dimensions"));
+    };
+
+    let data = &bytes[48..];
+    if data.len() < size {
+        return Err(err("truncated voxel data"));
+    }
+    let voxels: Vec<f32> = if encoding == 1 {
+        data.chunks_exact(4 * channels)
+            .take(count)
+            .map(|c| f32::from_le_bytes(c[..4].try_into().unwrap()))
+            .collect()
+    } else {
+        data.chunks_exact(channels)
+            .take(count)
+            .map(|c| c[0] as f32 / 255.0)
+            .collect()
+    };
+
+    Ok(VoxelGrid::new(resolution, bounds, voxels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A single-channel grid over the unit cube.
+    fn vol(encoding: i32, resolution: [i32; 3], data: &[u8]) -> Vec<u8> {
+        let mut bytes = b"VOL\x03".to_vec();
+        for n in [encoding, resolution[0], resolution[1], resolution[2], 1] {
+            bytes.extend(n.to_le_bytes());
+        }
+        for x in [0f32, 0.0, 0.0, 1.0, 1.0, 1.0] {
+            bytes.extend(x.to_le_bytes());
+        }
+        bytes.extend(data);
+        bytes
+    }
+
+    fn message(result: Result<VoxelGrid, LoadError>) -> String {
+        match result {
+            Err(LoadError::Format { message, .. }) => message,
+            Err(e) => panic!("expected a format error, got {e}"),
+            Ok(_) => panic!("expected a format error"),
+        }
+    }
+
+    #[test]
+    fn float_and_byte_voxels_are_read() {
+        let path = Path::new("a.vol");
+        let grid = parse_vol(path, &vol(1, [1, 1, 1], &2.5f32.to_le_bytes())).unwrap();
+        assert_eq!(grid.max(), 2.5);
+
+        let grid = parse_vol(path, &vol(3, [2, 1, 1], &[0, 255])).unwrap();
+        assert_eq!(grid.max(), 1.0);
+    }
+
+    #[test]
+    fn malformed_headers_are_rejected() {
+        let path = Path::new("a.vol");
+        let mut bytes = vol(1, [1, 1, 1], &[0; 4]);
+        bytes[0] = b'X';
+        assert_eq!(message(parse_vol(path, &bytes)), "not a .vol file");
+
+        let mut bytes = vol(1, [1, 1, 1], &[0; 4]);
+        bytes[3] = 2;
+        assert_eq!(message(parse_vol(path, &bytes)), "unsupported .vol version");
+
+        let bytes = vol(1, [0, 1, 1], &[]);
+        assert_eq!(message(parse_vol(path, &bytes)), "invalid grid dimensions");
+
+        let bytes = vol(1, [i32::MAX; 3], &[]);
+        assert_eq!(message(parse_vol(path, &bytes)), "invalid grid dimensions");
+    }
+
+    #[test]
+    fn encodings_and_sizes_are_checked() {
+        let path = Path::new("a.vol");
+        let bytes = vol(2, [1, 1, 1], &[0; 4]);
+        assert_eq!(
+            message(parse_vol(path, &bytes)),
+            "unsupported .vol encoding"
+        );
+
+        let bytes = vol(1, [2, 2, 2], &[0; 28]);
+        assert_eq!(message(parse_vol(path, &bytes)), "truncated voxel data");
+    }
+}
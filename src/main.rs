@@ -0,0 +1,226 @@
+use clap::{Parser, ValueEnum};
+use lumo::color::Color;
+use lumo::loader::SceneFile;
+use lumo::render::Image;
+use lumo::tonemap::ToneMap;
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
+
+#[derive(Parser)]
+#[command(version, about = "Render a lumo scene file")]
+struct Args {
+    /// Scene description file (TOML)
+    scene: PathBuf,
+
+    /// Output image; the format is chosen from the extension
+    #[arg(short, long, default_value = "out.png")]
+    output: PathBuf,
+
+    /// Image width in pixels
+    #[arg(long)]
+    width: Option<usize>,
+
+    /// Image height in pixels
+    #[arg(long)]
+    height: Option<usize>,
+
+    /// Samples per pixel
+    #[arg(short, long)]
+    samples: Option<u32>,
+
+    /// Maximum ray depth
+    #[arg(short, long)]
+    depth: Option<u32>,
+
+    /// Sample adaptively, stopping pixels whose relative standard error falls below this
+    #[arg(long, value_parser = positive)]
+    target_error: Option<f64>,
+
+    /// Samples per pixel before adaptive sampling may stop a pixel
+    #[arg(long)]
+    min_samples: Option<u32>,
+
+    /// Also save the number of samples taken by each pixel, relative to the maximum, as a linear
+    /// image
+    #[arg(long)]
+    sample_map: Option<PathBuf>,
+
+    /// Render progressively in passes of this many samples per pixel, saving the output after
+    /// each pass
+    #[arg(long)]
+    pass_samples: Option<u32>,
+
+    /// Number of worker threads (defaults to the number of CPUs)
+    #[arg(short = 'j', long)]
+    threads: Option<usize>,
+
+    /// Exposure adjustment in stops, applied to 8-bit outputs
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    exposure: f64,
+
+    /// Tone mapping operator, applied to 8-bit outputs
+    #[arg(long, value_enum, default_value_t = Operator::Clamp)]
+    tone_map: Operator,
+
+    /// White point for the extended Reinhard operator
+    #[arg(long, default_value_t = 4.0)]
+    white: f64,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Operator {
+    Clamp,
+    Reinhard,
+    ExtendedReinhard,
+    Aces,
+    Agx,
+}
+
+fn positive(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(x) if x > 0.0 && x.is_finite() => Ok(x),
+        Ok(_) => Err(String::from("must be positive and finite")),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    Image::check_format(&args.output)?;
+    if let Some(path) = &args.sample_map {
+        Image::check_format(path)?;
+    }
+
+    if let Some(threads) = args.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()?;
+    }
+
+    let mut file = SceneFile::open(&args.scene)?;
+    let settings = &mut file.render;
+    let aspect_ratio = settings.width as f64 / settings.height as f64;
+
+    match (args.width, args.height) {
+        (Some(width), Some(height)) => {
+            settings.width = width;
+            settings.height = height;
+        }
+        (Some(width), None) => {
+            settings.width = width;
+            settings.height = ((width as f64 / aspect_ratio) as usize).max(1);
+        }
+        (None, Some(height)) => {
+            settings.width = ((height as f64 * aspect_ratio) as usize).max(1);
+            settings.height = height;
+        }
+        (None, None) => {}
+    }
+
+    if let Some(samples) = args.samples {
+        settings.samples = samples;
+    }
+
+    if let Some(depth) = args.depth {
+        settings.depth = depth;
+    }
+
+    if let Some(target_error) = args.target_error {
+        settings.target_error = Some(target_error);
+    }
+
+    if let Some(min_samples) = args.min_samples {
+        settings.min_samples = min_samples;
+    }
+
+    let settings = file.render.clone();
+    let scene = file.build()?;
+
+    let tone_map = match args.tone_map {
+        Operator::Clamp => ToneMap::Clamp,
+        Operator::Reinhard => ToneMap::Reinhard,
+        Operator::ExtendedReinhard => ToneMap::ExtendedReinhard { white: args.white },
+        Operator::Aces => ToneMap::Aces,
+        Operator::Agx => ToneMap::Agx,
+    };
+    let save = |buffer: Vec<Color>| {
+        Image::new(settings.width, settings.height, buffer)
+            .with_exposure(args.exposure)
+            .with_tone_map(tone_map)
+            .save(&args.output)
+    };
+
+    let start = Instant::now();
+    let mut sample_counts = Vec::new();
+    let mut saved = Ok(());
+    let buffer = match args.pass_samples {
+        Some(pass_samples) => {
+            scene
+                .renderer
+                .render_progressive(scene.environment, pass_samples.max(1), |pass| {
+                    sample_counts = pass.sample_counts.to_vec();
+                    saved = save(pass.image.to_vec());
+                    if let Err(e) = &saved {
+                        eprintln!("warning: failed to save pass {}: {}", pass.index, e);
+                    }
+                })
+        }
+        None => {
+            let (buffer, counts) = scene.renderer.render_with_sample_counts(scene.environment);
+            sample_counts = counts;
+            buffer
+        }
+    };
+    let elapsed = start.elapsed();
+
+    // The last pass of a progressive render is the final image, and has been saved already.
+    match args.pass_samples {
+        Some(_) => saved?,
+        None => save(buffer)?,
+    }
+
+    if let Some(path) = &args.sample_map {
+        let max = settings.samples.max(1) as f64;
+        let map = sample_counts
+            .iter()
+            .map(|&n| Color::new(n as f64 / max, n as f64 / max, n as f64 / max))
+            .collect();
+        Image::new(settings.width, settings.height, map)
+            .linear()
+            .save(path)?;
+    }
+
+    let samples: f64 = sample_counts.iter().map(|&n| n as f64).sum();
+    println!("output:     {}", args.output.display());
+    println!(
+        "resolution: {}x{} @ {} spp, depth {}",
+        settings.width, settings.height, settings.samples, settings.depth
+    );
+    if let Some(target_error) = settings.target_error {
+        let pixels = (settings.width * settings.height).max(1) as f64;
+        println!(
+            "adaptive:   {:.1} spp on average, target error {}",
+            samples / pixels,
+            target_error
+        );
+    }
+    println!("threads:    {}", rayon::current_num_threads());
+    println!("time:       {:.2?}", elapsed);
+    println!(
+        "throughput: {:.3} Msamples/s",
+        samples / elapsed.as_secs_f64() / 1e6
+    );
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
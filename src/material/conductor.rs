@@ -0,0 +1,120 @@
+use super::microfacet::{fresnel_conductor, reflect, Frame, Ggx, MIN_ROUGHNESS};
+use super::{BsdfSample, Material};
+use crate::color::Color;
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+
+// A GGX microfacet metal described by its complex index of refraction `eta + i k`.
+pub struct Conductor {
+    eta: Color,
+    k: Color,
+    roughness: f64,
+}
+
+impl Conductor {
+    pub fn new(eta: Color, k: Color) -> Self {
+        Self {
+            eta,
+            k,
+            roughness: 0.0,
+        }
+    }
+
+    // RGB-averaged measurements at roughly 650, 550 and 450 nm.
+    pub fn gold() -> Self {
+        Self::new(
+            Color::new(0.143, 0.374, 1.442),
+            Color::new(3.983, 2.385, 1.603),
+        )
+    }
+
+    pub fn copper() -> Self {
+        Self::new(
+            Color::new(0.200, 0.924, 1.102),
+            Color::new(3.912, 2.452, 2.142),
+        )
+    }
+
+    pub fn aluminium() -> Self {
+        Self::new(
+            Color::new(1.657, 0.880, 0.521),
+            Color::new(9.224, 6.270, 4.837),
+        )
+    }
+
+    pub fn with_roughness(mut self, roughness: f64) -> Self {
+        self.roughness = roughness.clamp(0.0, 1.0);
+        self
+    }
+
+    fn is_smooth(&self) -> bool {
+        self.roughness < MIN_ROUGHNESS
+    }
+}
+
+impl Material for Conductor {
+    fn sample(&self, r: &Ray, hit: &HitRecord) -> Option<BsdfSample> {
+        let frame = Frame::new(&hit.n);
+        let wo = frame.to_local(&-r.direction.normalized());
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        if self.is_smooth() {
+            let wi = Vector3::new(-wo.x, -wo.y, wo.z);
+            let f = fresnel_conductor(wo.z, &self.eta, &self.k);
+            return Some(BsdfSample::delta(frame.to_world(&wi), f));
+        }
+
+        let ggx = Ggx::new(self.roughness);
+        let h = ggx.sample_visible(&wo);
+        let wi = reflect(&wo, &h);
+        if wi.z <= 0.0 {
+            return None;
+        }
+
+        let pdf = ggx.pdf_visible(&wo, &h) / (4.0 * wo.dot(&h));
+        let f = fresnel_conductor(wo.dot(&h), &self.eta, &self.k);
+        let weight = ggx.g(&wo, &wi) / ggx.g1(&wo) * f;
+
+        Some(BsdfSample::new(frame.to_world(&wi), weight, pdf))
+    }
+
+    fn eval(&self, r: &Ray, hit: &HitRecord, direction: &Vector3) -> Color {
+        if self.is_smooth() {
+            return Color::BLACK;
+        }
+
+        let frame = Frame::new(&hit.n);
+        let wo = frame.to_local(&-r.direction.normalized());
+        let wi = frame.to_local(&direction.normalized());
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return Color::BLACK;
+        }
+
+        let ggx = Ggx::new(self.roughness);
+        let h = (&wo + &wi).normalized();
+        let f = fresnel_conductor(wo.dot(&h), &self.eta, &self.k);
+
+        ggx.d(&h) * ggx.g(&wo, &wi) / (4.0 * wo.z) * f
+    }
+
+    fn pdf(&self, r: &Ray, hit: &HitRecord, direction: &Vector3) -> f64 {
+        if self.is_smooth() {
+            return 0.0;
+        }
+
+        let frame = Frame::new(&hit.n);
+        let wo = frame.to_local(&-r.direction.normalized());
+        let wi = frame.to_local(&direction.normalized());
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return 0.0;
+        }
+
+        let ggx = Ggx::new(self.roughness);
+        let h = (&wo + &wi).normalized();
+
+        ggx.pdf_visible(&wo, &h) / (4.0 * wo.dot(&h))
+    }
+}
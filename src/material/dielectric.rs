@@ -1,4 +1,4 @@
-use super::{reflect, Material};
+use super::{reflect, BsdfSample, Material};
 use crate::color::Color;
 use crate::hittable::HitRecord;
 use crate::ray::Ray;
@@ -15,7 +15,7 @@ impl Dielectric {
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, r: &Ray, hit: HitRecord) -> Option<(Color, Ray)> {
+    fn sample(&self, r: &Ray, hit: &HitRecord) -> Option<BsdfSample> {
         let direction = r.direction.normalized();
         let cos_theta = (-direction.dot(&hit.n)).min(1.0);
         let index = if hit.front_face {
@@ -32,7 +32,7 @@ impl Material for Dielectric {
             refract(&direction, &hit.n, index)
         };
 
-        Some((Color::WHITE, Ray::new(hit.p, direction)))
+        Some(BsdfSample::delta(direction, Color::WHITE))
     }
 }
 
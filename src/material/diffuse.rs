@@ -1,28 +1,48 @@
-use super::Material;
+use super::{BsdfSample, Material};
 use crate::color::Color;
 use crate::hittable::HitRecord;
-use crate::random::random_unit_vector;
+use crate::random::random_cosine_direction;
 use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
+use crate::vector3::Vector3;
+use std::f64::consts::PI;
+use std::sync::Arc;
 
 pub struct Diffuse {
-    color: Color,
+    albedo: Arc<dyn Texture>,
 }
 
 impl Diffuse {
     pub fn new(color: Color) -> Self {
-        Self { color }
+        Self::from_texture(Arc::new(SolidColor::new(color)))
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>) -> Self {
+        Self { albedo }
     }
 }
 
 impl Material for Diffuse {
-    fn scatter(&self, _: &Ray, hit: HitRecord) -> Option<(Color, Ray)> {
-        let direction = &hit.n + random_unit_vector();
-        let direction = if direction.is_nearly_zero() {
-            hit.n
-        } else {
-            direction
-        };
-
-        Some((self.color.clone(), Ray::new(hit.p, direction)))
+    fn sample(&self, _: &Ray, hit: &HitRecord) -> Option<BsdfSample> {
+        let local = random_cosine_direction();
+        let (u, v) = hit.n.orthonormal_basis();
+        let direction = local.x * u + local.y * v + local.z * &hit.n;
+        let pdf = local.z / PI;
+
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        let albedo = self.albedo.value(hit.u, hit.v, &hit.p);
+        Some(BsdfSample::new(direction, albedo, pdf))
+    }
+
+    fn eval(&self, _: &Ray, hit: &HitRecord, direction: &Vector3) -> Color {
+        let cos = hit.n.dot(&direction.normalized()).max(0.0);
+        cos / PI * self.albedo.value(hit.u, hit.v, &hit.p)
+    }
+
+    fn pdf(&self, _: &Ray, hit: &HitRecord, direction: &Vector3) -> f64 {
+        hit.n.dot(&direction.normalized()).max(0.0) / PI
     }
 }
@@ -0,0 +1,43 @@
+use super::{BsdfSample, Material};
+use crate::color::Color;
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
+use std::sync::Arc;
+
+pub struct DiffuseLight {
+    emit: Arc<dyn Texture>,
+    intensity: f64,
+}
+
+impl DiffuseLight {
+    pub fn new(color: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(color)))
+    }
+
+    pub fn from_texture(emit: Arc<dyn Texture>) -> Self {
+        Self {
+            emit,
+            intensity: 1.0,
+        }
+    }
+
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+}
+
+impl Material for DiffuseLight {
+    fn sample(&self, _: &Ray, _: &HitRecord) -> Option<BsdfSample> {
+        None
+    }
+
+    fn emitted(&self, hit: &HitRecord) -> Color {
+        if hit.front_face {
+            self.intensity * self.emit.value(hit.u, hit.v, &hit.p)
+        } else {
+            Color::BLACK
+        }
+    }
+}
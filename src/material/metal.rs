@@ -1,36 +1,43 @@
-use super::Material;
+use super::{BsdfSample, Material};
 use crate::color::Color;
 use crate::hittable::HitRecord;
 use crate::random::random_in_unit_sphere;
 use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
 use crate::vector3::Vector3;
+use std::sync::Arc;
 
 pub struct Metal {
-    color: Color,
+    albedo: Arc<dyn Texture>,
     fuzz: f64,
 }
 
 impl Metal {
     pub fn new(color: Color) -> Self {
-        Self { color, fuzz: 0.0 }
+        Self::from_texture(Arc::new(SolidColor::new(color)))
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>) -> Self {
+        Self { albedo, fuzz: 0.0 }
     }
 
     pub fn with_fuzz(self, fuzz: f64) -> Self {
         Self {
-            color: self.color,
+            albedo: self.albedo,
             fuzz: fuzz.clamp(0.0, 1.0),
         }
     }
 }
 
 impl Material for Metal {
-    fn scatter(&self, r: &Ray, hit: HitRecord) -> Option<(Color, Ray)> {
+    fn sample(&self, r: &Ray, hit: &HitRecord) -> Option<BsdfSample> {
         let reflected = reflect(&r.direction.normalized(), &hit.n);
         let offset = self.fuzz * random_in_unit_sphere();
-        let scattered = Ray::new(hit.p, reflected + offset);
+        let color = self.albedo.value(hit.u, hit.v, &hit.p);
+        let direction = reflected + offset;
 
-        if 0.0 < scattered.direction.dot(&hit.n) {
-            Some((self.color.clone(), scattered))
+        if 0.0 < direction.dot(&hit.n) {
+            Some(BsdfSample::delta(direction, color))
         } else {
             None
         }
@@ -0,0 +1,207 @@
+use crate::color::Color;
+use crate::vector3::Vector3;
+use std::f64::consts::PI;
+
+// Below this roughness the lobes are treated as perfectly specular.
+pub(super) const MIN_ROUGHNESS: f64 = 1e-3;
+
+// Shading frame with the normal along +z.
+pub(super) struct Frame {
+    u: Vector3,
+    v: Vector3,
+    n: Vector3,
+}
+
+impl Frame {
+    pub(super) fn new(n: &Vector3) -> Self {
+        let (u, v) = n.orthonormal_basis();
+        Self { u, v, n: n.clone() }
+    }
+
+    pub(super) fn to_local(&self, w: &Vector3) -> Vector3 {
+        Vector3::new(w.dot(&self.u), w.dot(&self.v), w.dot(&self.n))
+    }
+
+    pub(super) fn to_world(&self, w: &Vector3) -> Vector3 {
+        w.x * &self.u + w.y * &self.v + w.z * &self.n
+    }
+}
+
+// Isotropic GGX / Trowbridge-Reitz distribution over local-frame microfacet normals.
+pub(super) struct Ggx {
+    alpha: f64,
+}
+
+impl Ggx {
+    pub(super) fn new(roughness: f64) -> Self {
+        Self {
+            alpha: (roughness * roughness).max(MIN_ROUGHNESS * MIN_ROUGHNESS),
+        }
+    }
+
+    pub(super) fn d(&self, h: &Vector3) -> f64 {
+        if h.z <= 0.0 {
+            return 0.0;
+        }
+
+        let a2 = self.alpha * self.alpha;
+        let t = (a2 - 1.0) * h.z * h.z + 1.0;
+        a2 / (PI * t * t)
+    }
+
+    fn lambda(&self, w: &Vector3) -> f64 {
+        let cos2 = w.z * w.z;
+        if cos2 == 0.0 {
+            return f64::INFINITY;
+        }
+
+        let tan2 = (1.0 - cos2).max(0.0) / cos2;
+        0.5 * ((1.0 + self.alpha * self.alpha * tan2).sqrt() - 1.0)
+    }
+
+    pub(super) fn g1(&self, w: &Vector3) -> f64 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    // Height-correlated masking-shadowing.
+    pub(super) fn g(&self, wo: &Vector3, wi: &Vector3) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    // Density of `sample_visible` for `h`.
+    pub(super) fn pdf_visible(&self, wo: &Vector3, h: &Vector3) -> f64 {
+        if wo.z <= 0.0 {
+            return 0.0;
+        }
+
+        self.g1(wo) * wo.dot(h).max(0.0) * self.d(h) / wo.z
+    }
+
+    // Samples a normal visible from `wo`, following Heitz, "Sampling the GGX Distribution of
+    // Visible Normals" (2018).
+    pub(super) fn sample_visible(&self, wo: &Vector3) -> Vector3 {
+        let (xi1, xi2) = rand::random::<(f64, f64)>();
+        let vh = Vector3::new(self.alpha * wo.x, self.alpha * wo.y, wo.z).normalized();
+
+        let len2 = vh.x * vh.x + vh.y * vh.y;
+        let t1 = if len2 > 0.0 {
+            Vector3::new(-vh.y, vh.x, 0.0) / len2.sqrt()
+        } else {
+            Vector3::new(1.0, 0.0, 0.0)
+        };
+        let t2 = vh.cross(&t1);
+
+        let r = xi1.sqrt();
+        let phi = 2.0 * PI * xi2;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z);
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).sqrt() + s * r * phi.sin();
+        let nh = p1 * t1 + p2 * t2 + (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt() * vh;
+
+        Vector3::new(self.alpha * nh.x, self.alpha * nh.y, nh.z.max(0.0)).normalized()
+    }
+}
+
+pub(super) fn reflect(wo: &Vector3, h: &Vector3) -> Vector3 {
+    2.0 * wo.dot(h) * h - wo
+}
+
+// Refracts `wo` through `h`, where `eta` is the ratio of the transmitted to the incident index.
+pub(super) fn refract(wo: &Vector3, h: &Vector3, eta: f64) -> Option<Vector3> {
+    let cos_i = wo.dot(h);
+    let sin2_t = (1.0 - cos_i * cos_i).max(0.0) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return None;
+    }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    Some((cos_i / eta - cos_t) * h - wo / eta)
+}
+
+pub(super) fn fresnel_dielectric(cos_i: f64, eta: f64) -> f64 {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return 1.0;
+    }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    let rs = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    let rp = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    0.5 * (rs * rs + rp * rp)
+}
+
+pub(super) fn fresnel_conductor(cos_i: f64, eta: &Color, k: &Color) -> Color {
+    let f = |eta: f64, k: f64| {
+        let cos2 = cos_i.clamp(0.0, 1.0).powi(2);
+        let sin2 = 1.0 - cos2;
+        let t0 = eta * eta - k * k - sin2;
+        let a2b2 = (t0 * t0 + 4.0 * eta * eta * k * k).sqrt();
+        let a = (0.5 * (a2b2 + t0)).max(0.0).sqrt();
+        let t1 = a2b2 + cos2;
+        let t2 = 2.0 * cos2.sqrt() * a;
+        let rs = (t1 - t2) / (t1 + t2);
+        let t3 = cos2 * a2b2 + sin2 * sin2;
+        let t4 = t2 * sin2;
+        let rp = rs * (t3 - t4) / (t3 + t4);
+        0.5 * (rs + rp)
+    };
+
+    Color::new(f(eta.r, k.r), f(eta.g, k.g), f(eta.b, k.b))
+}
+
+// Samples the rough dielectric lobe of Walter et al., "Microfacet Models for Refraction through
+// Rough Surfaces" (2007), picking reflection or refraction in proportion to Fresnel. `eta` is
+// the ratio of the inner to the outer index as seen from `wo`.
+pub(super) fn sample_dielectric(ggx: &Ggx, wo: &Vector3, eta: f64) -> Option<Vector3> {
+    let h = ggx.sample_visible(wo);
+    let reflected = rand::random::<f64>() < fresnel_dielectric(wo.dot(&h), eta);
+    let wi = if reflected {
+        reflect(wo, &h)
+    } else {
+        refract(wo, &h, eta)?
+    };
+
+    // Samples ending up on the wrong side of the surface are absorbed.
+    ((wi.z > 0.0) == reflected).then_some(wi)
+}
+
+// The BSDF times the cosine and the density of `sample_dielectric` for `wi`. Transmitted
+// radiance is not scaled by the squared index ratio; the factors cancel for closed objects.
+pub(super) fn eval_dielectric(ggx: &Ggx, wo: &Vector3, wi: &Vector3, eta: f64) -> (f64, f64) {
+    if wo.z <= 0.0 || wi.z == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let reflected = wi.z > 0.0;
+    let h = if reflected {
+        (wo + wi).normalized()
+    } else {
+        let h = -(wo + eta * wi).normalized();
+        if h.z < 0.0 {
+            -h
+        } else {
+            h
+        }
+    };
+
+    let (cos_o, cos_i) = (wo.dot(&h), wi.dot(&h));
+    if cos_o <= 0.0 || (cos_i > 0.0) != reflected {
+        return (0.0, 0.0);
+    }
+
+    let f = fresnel_dielectric(cos_o, eta);
+    let d = ggx.d(&h);
+    let g = ggx.g(wo, wi);
+    let pdf_h = ggx.pdf_visible(wo, &h);
+
+    if reflected {
+        (f * d * g / (4.0 * wo.z), f * pdf_h / (4.0 * cos_o))
+    } else {
+        let jacobian = eta * eta * cos_i.abs() / (cos_o + eta * cos_i).powi(2);
+        (
+            (1.0 - f) * d * g * jacobian * cos_o / wo.z,
+            (1.0 - f) * pdf_h * jacobian,
+        )
+    }
+}
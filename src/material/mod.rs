@@ -1,15 +1,71 @@
 use crate::color::Color;
 use crate::hittable::HitRecord;
 use crate::ray::Ray;
+use crate::vector3::Vector3;
+
+pub struct BsdfSample {
+    pub direction: Vector3,
+    // The BSDF times the cosine divided by `pdf`; for delta lobes, simply the attenuation.
+    pub weight: Color,
+    pub pdf: f64,
+    // Delta lobes can't be evaluated for an arbitrary direction, so `pdf` is not a density
+    // and the sample doesn't take part in light sampling.
+    pub delta: bool,
+}
+
+impl BsdfSample {
+    pub fn new(direction: Vector3, weight: Color, pdf: f64) -> Self {
+        Self {
+            direction,
+            weight,
+            pdf,
+            delta: false,
+        }
+    }
+
+    pub fn delta(direction: Vector3, weight: Color) -> Self {
+        Self {
+            direction,
+            weight,
+            pdf: 1.0,
+            delta: true,
+        }
+    }
+}
 
 pub trait Material: Send + Sync {
-    fn scatter(&self, r: &Ray, rec: HitRecord) -> Option<(Color, Ray)>;
+    fn sample(&self, r: &Ray, rec: &HitRecord) -> Option<BsdfSample>;
+
+    // The BSDF times the cosine towards `direction`, excluding any delta lobes.
+    fn eval(&self, _r: &Ray, _rec: &HitRecord, _direction: &Vector3) -> Color {
+        Color::BLACK
+    }
+
+    // The solid angle density with which `sample` picks `direction`, excluding any delta lobes.
+    fn pdf(&self, _r: &Ray, _rec: &HitRecord, _direction: &Vector3) -> f64 {
+        0.0
+    }
+
+    fn emitted(&self, _rec: &HitRecord) -> Color {
+        Color::BLACK
+    }
 }
 
+mod conductor;
 mod dielectric;
 mod diffuse;
+mod light;
 mod metal;
+mod microfacet;
+mod phase;
+mod principled;
+mod rough_dielectric;
 
+pub use conductor::*;
 pub use dielectric::*;
 pub use diffuse::*;
+pub use light::*;
 pub use metal::*;
+pub use phase::*;
+pub use principled::*;
+pub use rough_dielectric::*;
@@ -0,0 +1,95 @@
+use super::{BsdfSample, Material};
+use crate::color::Color;
+use crate::hittable::HitRecord;
+use crate::random::random_unit_vector;
+use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
+use crate::vector3::Vector3;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+// Phase functions for participating media. They ignore the surface normal and the cosine term;
+// `albedo` is the single scattering albedo.
+pub struct Isotropic {
+    albedo: Arc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(color: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(color)))
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn sample(&self, _: &Ray, hit: &HitRecord) -> Option<BsdfSample> {
+        let albedo = self.albedo.value(hit.u, hit.v, &hit.p);
+        Some(BsdfSample::new(random_unit_vector(), albedo, 0.25 / PI))
+    }
+
+    fn eval(&self, _: &Ray, hit: &HitRecord, _: &Vector3) -> Color {
+        0.25 / PI * self.albedo.value(hit.u, hit.v, &hit.p)
+    }
+
+    fn pdf(&self, _: &Ray, _: &HitRecord, _: &Vector3) -> f64 {
+        0.25 / PI
+    }
+}
+
+// Henyey-Greenstein phase function; positive `g` favours forward scattering.
+pub struct HenyeyGreenstein {
+    albedo: Arc<dyn Texture>,
+    g: f64,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(color: Color, g: f64) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(color)), g)
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>, g: f64) -> Self {
+        Self {
+            albedo,
+            g: g.clamp(-0.99, 0.99),
+        }
+    }
+
+    fn phase(&self, cos: f64) -> f64 {
+        let g = self.g;
+        let denom = 1.0 + g * g - 2.0 * g * cos;
+        0.25 / PI * (1.0 - g * g) / (denom * denom.sqrt())
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn sample(&self, r: &Ray, hit: &HitRecord) -> Option<BsdfSample> {
+        let (xi1, xi2) = rand::random::<(f64, f64)>();
+        let g = self.g;
+        let cos = if g.abs() < 1e-3 {
+            1.0 - 2.0 * xi1
+        } else {
+            let s = (1.0 - g * g) / (1.0 + g - 2.0 * g * xi1);
+            ((1.0 + g * g - s * s) / (2.0 * g)).clamp(-1.0, 1.0)
+        };
+
+        let w = r.direction.normalized();
+        let (u, v) = w.orthonormal_basis();
+        let sin = (1.0 - cos * cos).max(0.0).sqrt();
+        let phi = 2.0 * PI * xi2;
+        let direction = sin * phi.cos() * u + sin * phi.sin() * v + cos * w;
+
+        let albedo = self.albedo.value(hit.u, hit.v, &hit.p);
+        Some(BsdfSample::new(direction, albedo, self.phase(cos)))
+    }
+
+    fn eval(&self, r: &Ray, hit: &HitRecord, direction: &Vector3) -> Color {
+        self.pdf(r, hit, direction) * self.albedo.value(hit.u, hit.v, &hit.p)
+    }
+
+    fn pdf(&self, r: &Ray, _: &HitRecord, direction: &Vector3) -> f64 {
+        self.phase(r.direction.normalized().dot(&direction.normalized()))
+    }
+}
@@ -0,0 +1,280 @@
+use super::microfacet::{eval_dielectric, reflect, sample_dielectric, Frame, Ggx};
+use super::{BsdfSample, Material};
+use crate::color::Color;
+use crate::hittable::HitRecord;
+use crate::random::random_cosine_direction;
+use crate::ray::Ray;
+use crate::texture::{SolidColor, Texture};
+use crate::vector3::Vector3;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+const CLEARCOAT_ROUGHNESS: f64 = 0.2;
+
+// A metallic/roughness uber material after Burley, "Physically Based Shading at Disney" (2012),
+// with the transmission lobe of the 2015 follow-up. Scalar parameters are read from the
+// luminance of their textures.
+pub struct Principled {
+    base_color: Arc<dyn Texture>,
+    metallic: Arc<dyn Texture>,
+    roughness: Arc<dyn Texture>,
+    specular: Arc<dyn Texture>,
+    specular_tint: Arc<dyn Texture>,
+    sheen: Arc<dyn Texture>,
+    clearcoat: Arc<dyn Texture>,
+    transmission: Arc<dyn Texture>,
+    ior: Arc<dyn Texture>,
+}
+
+fn constant(value: f64) -> Arc<dyn Texture> {
+    Arc::new(SolidColor::new(Color::new(value, value, value)))
+}
+
+impl Principled {
+    pub fn new(base_color: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(base_color)))
+    }
+
+    pub fn from_texture(base_color: Arc<dyn Texture>) -> Self {
+        Self {
+            base_color,
+            metallic: constant(0.0),
+            roughness: constant(0.5),
+            specular: constant(0.5),
+            specular_tint: constant(0.0),
+            sheen: constant(0.0),
+            clearcoat: constant(0.0),
+            transmission: constant(0.0),
+            ior: constant(1.5),
+        }
+    }
+
+    pub fn with_metallic(self, metallic: f64) -> Self {
+        self.with_metallic_map(constant(metallic))
+    }
+
+    pub fn with_metallic_map(mut self, metallic: Arc<dyn Texture>) -> Self {
+        self.metallic = metallic;
+        self
+    }
+
+    pub fn with_roughness(self, roughness: f64) -> Self {
+        self.with_roughness_map(constant(roughness))
+    }
+
+    pub fn with_roughness_map(mut self, roughness: Arc<dyn Texture>) -> Self {
+        self.roughness = roughness;
+        self
+    }
+
+    pub fn with_specular(self, specular: f64) -> Self {
+        self.with_specular_map(constant(specular))
+    }
+
+    pub fn with_specular_map(mut self, specular: Arc<dyn Texture>) -> Self {
+        self.specular = specular;
+        self
+    }
+
+    pub fn with_specular_tint(self, specular_tint: f64) -> Self {
+        self.with_specular_tint_map(constant(specular_tint))
+    }
+
+    pub fn with_specular_tint_map(mut self, specular_tint: Arc<dyn Texture>) -> Self {
+        self.specular_tint = specular_tint;
+        self
+    }
+
+    pub fn with_sheen(self, sheen: f64) -> Self {
+        self.with_sheen_map(constant(sheen))
+    }
+
+    pub fn with_sheen_map(mut self, sheen: Arc<dyn Texture>) -> Self {
+        self.sheen = sheen;
+        self
+    }
+
+    pub fn with_clearcoat(self, clearcoat: f64) -> Self {
+        self.with_clearcoat_map(constant(clearcoat))
+    }
+
+    pub fn with_clearcoat_map(mut self, clearcoat: Arc<dyn Texture>) -> Self {
+        self.clearcoat = clearcoat;
+        self
+    }
+
+    pub fn with_transmission(self, transmission: f64) -> Self {
+        self.with_transmission_map(constant(transmission))
+    }
+
+    pub fn with_transmission_map(mut self, transmission: Arc<dyn Texture>) -> Self {
+        self.transmission = transmission;
+        self
+    }
+
+    pub fn with_ior(self, ior: f64) -> Self {
+        self.with_ior_map(constant(ior))
+    }
+
+    pub fn with_ior_map(mut self, ior: Arc<dyn Texture>) -> Self {
+        self.ior = ior;
+        self
+    }
+
+    fn lobes(&self, hit: &HitRecord, wo: &Vector3) -> Lobes {
+        let scalar = |t: &Arc<dyn Texture>| t.value(hit.u, hit.v, &hit.p).luminance();
+
+        let base = self.base_color.value(hit.u, hit.v, &hit.p);
+        let metallic = scalar(&self.metallic).clamp(0.0, 1.0);
+        let roughness = scalar(&self.roughness).clamp(0.0, 1.0);
+        let specular = scalar(&self.specular).max(0.0);
+        let specular_tint = scalar(&self.specular_tint).clamp(0.0, 1.0);
+        let sheen = scalar(&self.sheen).max(0.0);
+        let clearcoat = scalar(&self.clearcoat).max(0.0);
+        let transmission = scalar(&self.transmission).clamp(0.0, 1.0);
+        let ior = scalar(&self.ior).max(1.0);
+
+        let lum = base.luminance();
+        let tint = if lum > 0.0 { &base / lum } else { Color::WHITE };
+        let dielectric_f0 = 0.08 * specular * Color::WHITE.lerp(&tint, specular_tint);
+
+        let diffuse = (1.0 - metallic) * (1.0 - transmission);
+        let glass = (1.0 - metallic) * transmission;
+        let f0 = dielectric_f0.lerp(&base, metallic);
+
+        // Lobes are picked in proportion to a rough estimate of their albedo.
+        let mut p = [
+            diffuse * (lum + sheen),
+            (1.0 - glass) * schlick(&f0, wo.z).luminance(),
+            0.25 * clearcoat * schlick(&Color::new(0.04, 0.04, 0.04), wo.z).r,
+            glass,
+        ];
+        let total: f64 = p.iter().sum();
+        if total > 0.0 {
+            p.iter_mut().for_each(|p| *p /= total);
+        }
+
+        Lobes {
+            sheen: sheen * Color::WHITE.lerp(&tint, 0.5),
+            base,
+            f0,
+            roughness,
+            diffuse,
+            glass,
+            clearcoat,
+            eta: if hit.front_face { ior } else { 1.0 / ior },
+            ggx: Ggx::new(roughness),
+            coat: Ggx::new(CLEARCOAT_ROUGHNESS),
+            p,
+        }
+    }
+}
+
+struct Lobes {
+    base: Color,
+    f0: Color,
+    sheen: Color,
+    roughness: f64,
+    diffuse: f64,
+    glass: f64,
+    clearcoat: f64,
+    eta: f64,
+    ggx: Ggx,
+    coat: Ggx,
+    // Probabilities of sampling the diffuse, specular, clearcoat and glass lobes.
+    p: [f64; 4],
+}
+
+impl Lobes {
+    // The BSDF times the cosine towards `wi` and the density of sampling it, in the local frame.
+    fn eval(&self, wo: &Vector3, wi: &Vector3) -> (Color, f64) {
+        let (mut f, mut pdf) = (Color::BLACK, 0.0);
+        if wo.z <= 0.0 {
+            return (f, pdf);
+        }
+
+        if wi.z > 0.0 {
+            let h = (wo + wi).normalized();
+            let cos_d = wi.dot(&h);
+
+            let fd90 = 0.5 + 2.0 * self.roughness * cos_d * cos_d;
+            let retro = (1.0 + (fd90 - 1.0) * (1.0 - wi.z).powi(5))
+                * (1.0 + (fd90 - 1.0) * (1.0 - wo.z).powi(5));
+            let diffuse = retro / PI * &self.base + (1.0 - cos_d).powi(5) * &self.sheen;
+            f = f + self.diffuse * wi.z * diffuse;
+            pdf += self.p[0] * wi.z / PI;
+
+            let specular = self.ggx.d(&h) * self.ggx.g(wo, wi) / (4.0 * wo.z);
+            f = f + (1.0 - self.glass) * specular * schlick(&self.f0, cos_d);
+            pdf += self.p[1] * self.ggx.pdf_visible(wo, &h) / (4.0 * wo.dot(&h));
+
+            let coat = self.coat.d(&h) * self.coat.g(wo, wi) / (4.0 * wo.z);
+            let fresnel = schlick(&Color::new(0.04, 0.04, 0.04), cos_d);
+            f = f + 0.25 * self.clearcoat * coat * fresnel;
+            pdf += self.p[2] * self.coat.pdf_visible(wo, &h) / (4.0 * wo.dot(&h));
+        }
+
+        if self.glass > 0.0 {
+            let (glass, glass_pdf) = eval_dielectric(&self.ggx, wo, wi, self.eta);
+            let color = if wi.z > 0.0 {
+                Color::WHITE
+            } else {
+                self.base.clone()
+            };
+            f = f + self.glass * glass * color;
+            pdf += self.p[3] * glass_pdf;
+        }
+
+        (f, pdf)
+    }
+}
+
+fn schlick(f0: &Color, cos: f64) -> Color {
+    let t = (1.0 - cos.clamp(0.0, 1.0)).powi(5);
+    f0.lerp(&Color::WHITE, t)
+}
+
+impl Material for Principled {
+    fn sample(&self, r: &Ray, hit: &HitRecord) -> Option<BsdfSample> {
+        let frame = Frame::new(&hit.n);
+        let wo = frame.to_local(&-r.direction.normalized());
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        let lobes = self.lobes(hit, &wo);
+        let xi = rand::random::<f64>();
+        let wi = if xi < lobes.p[0] {
+            random_cosine_direction()
+        } else if xi < lobes.p[0] + lobes.p[1] {
+            reflect(&wo, &lobes.ggx.sample_visible(&wo))
+        } else if xi < lobes.p[0] + lobes.p[1] + lobes.p[2] {
+            reflect(&wo, &lobes.coat.sample_visible(&wo))
+        } else {
+            sample_dielectric(&lobes.ggx, &wo, lobes.eta)?
+        };
+
+        let (f, pdf) = lobes.eval(&wo, &wi);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(BsdfSample::new(frame.to_world(&wi), f / pdf, pdf))
+    }
+
+    fn eval(&self, r: &Ray, hit: &HitRecord, direction: &Vector3) -> Color {
+        let frame = Frame::new(&hit.n);
+        let wo = frame.to_local(&-r.direction.normalized());
+        let wi = frame.to_local(&direction.normalized());
+
+        self.lobes(hit, &wo).eval(&wo, &wi).0
+    }
+
+    fn pdf(&self, r: &Ray, hit: &HitRecord, direction: &Vector3) -> f64 {
+        let frame = Frame::new(&hit.n);
+        let wo = frame.to_local(&-r.direction.normalized());
+        let wi = frame.to_local(&direction.normalized());
+
+        self.lobes(hit, &wo).eval(&wo, &wi).1
+    }
+}
@@ -0,0 +1,104 @@
+use super::microfacet::{
+    eval_dielectric, fresnel_dielectric, reflect, refract, sample_dielectric, Frame, Ggx,
+    MIN_ROUGHNESS,
+};
+use super::{BsdfSample, Material};
+use crate::color::Color;
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+
+// A GGX microfacet dielectric, e.g. frosted glass.
+pub struct RoughDielectric {
+    index: f64,
+    roughness: f64,
+}
+
+impl RoughDielectric {
+    pub fn new(index: f64) -> Self {
+        Self {
+            index,
+            roughness: 0.0,
+        }
+    }
+
+    pub fn with_roughness(mut self, roughness: f64) -> Self {
+        self.roughness = roughness.clamp(0.0, 1.0);
+        self
+    }
+
+    fn eta(&self, hit: &HitRecord) -> f64 {
+        if hit.front_face {
+            self.index
+        } else {
+            1.0 / self.index
+        }
+    }
+
+    fn is_smooth(&self) -> bool {
+        self.roughness < MIN_ROUGHNESS
+    }
+}
+
+impl Material for RoughDielectric {
+    fn sample(&self, r: &Ray, hit: &HitRecord) -> Option<BsdfSample> {
+        let frame = Frame::new(&hit.n);
+        let wo = frame.to_local(&-r.direction.normalized());
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        let eta = self.eta(hit);
+        if self.is_smooth() {
+            let n = Vector3::new(0.0, 0.0, 1.0);
+            let wi = if rand::random::<f64>() < fresnel_dielectric(wo.z, eta) {
+                reflect(&wo, &n)
+            } else {
+                refract(&wo, &n, eta)?
+            };
+            return Some(BsdfSample::delta(frame.to_world(&wi), Color::WHITE));
+        }
+
+        let ggx = Ggx::new(self.roughness);
+        let wi = sample_dielectric(&ggx, &wo, eta)?;
+        let (_, pdf) = eval_dielectric(&ggx, &wo, &wi, eta);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        // Both lobes are picked in proportion to their Fresnel weight, leaving only the
+        // shadowing term in the throughput.
+        let weight = ggx.g(&wo, &wi) / ggx.g1(&wo);
+        Some(BsdfSample::new(
+            frame.to_world(&wi),
+            weight * Color::WHITE,
+            pdf,
+        ))
+    }
+
+    fn eval(&self, r: &Ray, hit: &HitRecord, direction: &Vector3) -> Color {
+        if self.is_smooth() {
+            return Color::BLACK;
+        }
+
+        let frame = Frame::new(&hit.n);
+        let wo = frame.to_local(&-r.direction.normalized());
+        let wi = frame.to_local(&direction.normalized());
+        let (f, _) = eval_dielectric(&Ggx::new(self.roughness), &wo, &wi, self.eta(hit));
+
+        f * Color::WHITE
+    }
+
+    fn pdf(&self, r: &Ray, hit: &HitRecord, direction: &Vector3) -> f64 {
+        if self.is_smooth() {
+            return 0.0;
+        }
+
+        let frame = Frame::new(&hit.n);
+        let wo = frame.to_local(&-r.direction.normalized());
+        let wi = frame.to_local(&direction.normalized());
+        let (_, pdf) = eval_dielectric(&Ggx::new(self.roughness), &wo, &wi, self.eta(hit));
+
+        pdf
+    }
+}
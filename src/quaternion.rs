@@ -0,0 +1,100 @@
+use crate::matrix4::Matrix4;
+use crate::vector3::Vector3;
+
+// Unit quaternion representing a rotation, w + xi + yj + zk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quaternion {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Quaternion {
+    pub const IDENTITY: Self = Self::new(1.0, 0.0, 0.0, 0.0);
+
+    pub const fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Self { w, x, y, z }
+    }
+
+    // Counter-clockwise rotation by `angle` radians about `axis`, matching `Matrix4::rotation`.
+    pub fn from_axis_angle(axis: &Vector3, angle: f64) -> Self {
+        let a = axis.normalized();
+        let (sin, cos) = (0.5 * angle).sin_cos();
+        Self::new(cos, sin * a.x, sin * a.y, sin * a.z)
+    }
+
+    pub fn dot(&self, q: &Self) -> f64 {
+        self.w * q.w + self.x * q.x + self.y * q.y + self.z * q.z
+    }
+
+    pub fn normalized(&self) -> Self {
+        let n = self.dot(self).sqrt();
+        Self::new(self.w / n, self.x / n, self.y / n, self.z / n)
+    }
+
+    // Rotation angle between the two orientations, in [0, pi].
+    pub fn angle(&self, q: &Self) -> f64 {
+        2.0 * self.dot(q).abs().min(1.0).acos()
+    }
+
+    // Constant-speed interpolation along the shorter arc from `self` (s = 0) to `q` (s = 1).
+    pub fn slerp(&self, q: &Self, s: f64) -> Self {
+        let mut cos = self.dot(q);
+        let q = if cos < 0.0 {
+            cos = -cos;
+            Self::new(-q.w, -q.x, -q.y, -q.z)
+        } else {
+            q.clone()
+        };
+
+        let (a, b) = if cos > 0.9995 {
+            // Nearly parallel; fall back to a normalized lerp.
+            (1.0 - s, s)
+        } else {
+            let theta = cos.acos();
+            let sin = theta.sin();
+            (((1.0 - s) * theta).sin() / sin, (s * theta).sin() / sin)
+        };
+
+        Self::new(
+            a * self.w + b * q.w,
+            a * self.x + b * q.x,
+            a * self.y + b * q.y,
+            a * self.z + b * q.z,
+        )
+        .normalized()
+    }
+
+    pub fn to_matrix(&self) -> Matrix4 {
+        let Self { w, x, y, z } = *self;
+
+        Matrix4::new([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+                0.0,
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+                0.0,
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
@@ -29,6 +29,15 @@ pub fn random_in_hemisphere(n: &Vector3) -> Vector3 {
     }
 }
 
+// Cosine-weighted direction in the hemisphere around +z.
+pub fn random_cosine_direction() -> Vector3 {
+    let (xi1, xi2) = random::<(f64, f64)>();
+    let phi = 2.0 * std::f64::consts::PI * xi1;
+    let r = xi2.sqrt();
+
+    Vector3::new(r * phi.cos(), r * phi.sin(), (1.0 - xi2).sqrt())
+}
+
 pub fn random_in_unit_disk() -> Vector3 {
     loop {
         let v = Vector3::new(random_real(), random_real(), 0.0);
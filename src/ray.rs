@@ -3,11 +3,21 @@ use crate::vector3::Vector3;
 pub struct Ray {
     pub origin: Vector3,
     pub direction: Vector3,
+    pub time: f64,
 }
 
 impl Ray {
     pub fn new(origin: Vector3, direction: Vector3) -> Self {
-        Self { origin, direction }
+        Self {
+            origin,
+            direction,
+            time: 0.0,
+        }
+    }
+
+    pub fn with_time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
     }
 
     pub fn at(&self, t: f64) -> Vector3 {
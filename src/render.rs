@@ -1,16 +1,51 @@
+use crate::background::Background;
 use crate::camera::Camera;
 use crate::color::Color;
-use crate::hittable::{Hittable, Environment};
+use crate::hittable::{Environment, HitRecord, Hittable};
+use crate::light::Light;
 use crate::ray::Ray;
-use image::{Rgb, RgbImage};
+use crate::tonemap::ToneMap;
+use image::codecs::hdr::HdrEncoder;
+use image::error::{EncodingError, ImageFormatHint};
+use image::{ImageError, ImageFormat, Rgb, RgbImage};
 use indicatif::{ProgressBar, ProgressStyle};
-use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::path::Path;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    OpenExr,
+    Radiance,
+    Pfm,
+    Ldr(ImageFormat),
+}
+
+impl Format {
+    fn from_path(path: &Path) -> image::ImageResult<Self> {
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match ext.as_deref() {
+            Some("exr") => Ok(Self::OpenExr),
+            Some("hdr") => Ok(Self::Radiance),
+            Some("pfm") => Ok(Self::Pfm),
+            _ => ImageFormat::from_path(path).map(Self::Ldr),
+        }
+    }
+}
+
 pub struct Image {
     width: usize,
     height: usize,
     buffer: Vec<Color>,
+    layers: Vec<(String, Vec<Color>)>,
+    exposure: f64,
+    tone_map: ToneMap,
+    linear: bool,
 }
 
 impl Image {
@@ -19,21 +54,190 @@ impl Image {
             width,
             height,
             buffer,
+            layers: Vec::new(),
+            exposure: 0.0,
+            tone_map: ToneMap::default(),
+            linear: false,
         }
     }
 
+    pub fn with_exposure(mut self, exposure: f64) -> Self {
+        self.exposure = exposure;
+        self
+    }
+
+    pub fn with_tone_map(mut self, tone_map: ToneMap) -> Self {
+        self.tone_map = tone_map;
+        self
+    }
+
+    // Stores 8-bit outputs without the sRGB transfer function, for images holding data rather
+    // than radiance.
+    pub fn linear(mut self) -> Self {
+        self.linear = true;
+        self
+    }
+
+    pub fn with_layer(mut self, name: &str, buffer: Vec<Color>) -> Self {
+        assert_eq!(buffer.len(), self.width * self.height);
+        self.layers.push((name.to_string(), buffer));
+        self
+    }
+
+    pub fn check_format<P: AsRef<Path>>(path: P) -> image::ImageResult<()> {
+        Format::from_path(path.as_ref()).map(|_| ())
+    }
+
     pub fn save<P: AsRef<Path>>(self, path: P) -> image::ImageResult<()> {
+        let path = path.as_ref();
+
+        match Format::from_path(path)? {
+            Format::OpenExr => self.save_exr(path),
+            Format::Radiance => self.save_hdr(path),
+            Format::Pfm => self.save_pfm(path),
+            Format::Ldr(format) => self.save_ldr(path, format),
+        }
+    }
+
+    fn save_ldr(self, path: &Path, format: ImageFormat) -> image::ImageResult<()> {
         let mut img = RgbImage::new(self.width as u32, self.height as u32);
+        let scale = self.exposure.exp2();
 
         for (pixel, color) in img.pixels_mut().zip(self.buffer) {
-            let r = (255.0 * color.r.clamp(0.0, 1.0)) as u8;
-            let g = (255.0 * color.g.clamp(0.0, 1.0)) as u8;
-            let b = (255.0 * color.b.clamp(0.0, 1.0)) as u8;
+            let mut color = self.tone_map.apply(scale * color);
+            if !self.linear {
+                color = color.linear_to_srgb();
+            }
+            let r = (255.0 * color.r).round() as u8;
+            let g = (255.0 * color.g).round() as u8;
+            let b = (255.0 * color.b).round() as u8;
 
             *pixel = Rgb([r, g, b]);
         }
 
-        img.save(path)
+        img.save_with_format(path, format)
+    }
+
+    fn save_exr(self, path: &Path) -> image::ImageResult<()> {
+        use exr::prelude::*;
+
+        let channels = |prefix: &str, buffer: &[Color]| {
+            let channel = |name: &str, f: fn(&Color) -> f64| {
+                let samples = buffer.iter().map(|c| f(c) as f32).collect();
+                AnyChannel::new(
+                    format!("{}{}", prefix, name).as_str(),
+                    FlatSamples::F32(samples),
+                )
+            };
+
+            [
+                channel("R", |c| c.r),
+                channel("G", |c| c.g),
+                channel("B", |c| c.b),
+            ]
+        };
+
+        let mut list = SmallVec::new();
+        list.extend(channels("", &self.buffer));
+        for (name, buffer) in self.layers.iter() {
+            list.extend(channels(&format!("{}.", name), buffer));
+        }
+
+        let layer = Layer::new(
+            (self.width, self.height),
+            LayerAttributes::default(),
+            Encoding::SMALL_LOSSLESS,
+            AnyChannels::sort(list),
+        );
+
+        Image::from_layer(layer).write().to_file(path).map_err(|e| {
+            ImageError::Encoding(EncodingError::new(
+                ImageFormatHint::Exact(ImageFormat::OpenExr),
+                e,
+            ))
+        })
+    }
+
+    fn save_hdr(self, path: &Path) -> image::ImageResult<()> {
+        let pixels: Vec<Rgb<f32>> = self
+            .buffer
+            .iter()
+            .map(|c| Rgb([c.r as f32, c.g as f32, c.b as f32]))
+            .collect();
+
+        let writer = BufWriter::new(File::create(path)?);
+        HdrEncoder::new(writer).encode(&pixels, self.width, self.height)
+    }
+
+    fn save_pfm(self, path: &Path) -> image::ImageResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write!(writer, "PF\n{} {}\n-1.0\n", self.width, self.height)?;
+
+        // PFM stores scanlines bottom to top.
+        for row in self.buffer.chunks(self.width).rev() {
+            for c in row {
+                for v in [c.r, c.g, c.b] {
+                    writer.write_all(&(v as f32).to_le_bytes())?;
+                }
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+// The state of a progressive render after one of its passes.
+pub struct Pass<'a> {
+    pub index: usize,
+    // Samples per pixel accumulated so far by pixels still being sampled.
+    pub samples: u32,
+    pub image: &'a [Color],
+    // Samples taken by each pixel, which differ once adaptive sampling stops converged pixels.
+    pub sample_counts: &'a [u32],
+}
+
+// Stops sampling a pixel once the standard error of its mean luminance falls below
+// `target_error` relative to the mean, but not before `min_samples`.
+#[derive(Debug, Clone, Copy)]
+struct Adaptive {
+    min_samples: u32,
+    target_error: f64,
+}
+
+// Running per-pixel sums of the samples and of their luminance, for the variance estimate.
+#[derive(Clone)]
+struct Accumulator {
+    color: Color,
+    luminance: f64,
+    luminance_squared: f64,
+    samples: u32,
+    converged: bool,
+}
+
+impl Accumulator {
+    fn add(&mut self, other: Self) {
+        self.color = &self.color + other.color;
+        self.luminance += other.luminance;
+        self.luminance_squared += other.luminance_squared;
+        self.samples += other.samples;
+    }
+
+    fn mean(&self) -> Color {
+        &self.color / self.samples.max(1) as f64
+    }
+
+    fn relative_error(&self) -> f64 {
+        if self.samples < 2 {
+            return f64::INFINITY;
+        }
+
+        let n = self.samples as f64;
+        let mean = self.luminance / n;
+        let variance = ((self.luminance_squared - mean * self.luminance) / (n - 1.0)).max(0.0);
+        // Black pixels are converged once they stay black; the floor keeps nearly black ones
+        // from demanding an ever smaller absolute error.
+        (variance / n).sqrt() / mean.max(1e-3)
     }
 }
 
@@ -43,6 +247,7 @@ pub struct Renderer {
     samples: u32,
     depth: u32,
     camera: Camera,
+    adaptive: Option<Adaptive>,
 }
 
 impl Renderer {
@@ -59,42 +264,133 @@ impl Renderer {
             samples,
             depth,
             camera,
+            adaptive: None,
         }
     }
 
+    // Lets each pixel stop between `min_samples` and the sample count once the standard error
+    // of its luminance is below `target_error` times the mean, e.g. 0.01 for one percent.
+    pub fn with_adaptive_sampling(mut self, min_samples: u32, target_error: f64) -> Self {
+        assert!(min_samples > 0 && target_error > 0.0);
+        self.adaptive = Some(Adaptive {
+            min_samples,
+            target_error,
+        });
+        self
+    }
+
     pub fn render<H, B>(&self, env: Environment<H, B>) -> Vec<Color>
     where
         H: Hittable,
-        B: Fn(&Ray) -> Color + Send + Sync,
+        B: Background,
+    {
+        self.render_with_sample_counts(env).0
+    }
+
+    // Also returns the number of samples taken by each pixel.
+    pub fn render_with_sample_counts<H, B>(&self, env: Environment<H, B>) -> (Vec<Color>, Vec<u32>)
+    where
+        H: Hittable,
+        B: Background,
+    {
+        // Adaptive renders check for convergence every `min_samples`.
+        let pass_samples = self.adaptive.map_or(self.samples, |a| a.min_samples).max(1);
+
+        let mut sample_counts = Vec::new();
+        let image = self.render_progressive(env, pass_samples, |pass| {
+            sample_counts = pass.sample_counts.to_vec();
+        });
+        (image, sample_counts)
+    }
+
+    // Renders in passes of up to `pass_samples` samples per pixel, calling `on_pass` with the
+    // image so far after each one. The last pass is shorter if `pass_samples` doesn't divide
+    // the sample count. With adaptive sampling, converged pixels are left out of later passes
+    // and the render ends early once every pixel has converged.
+    pub fn render_progressive<H, B, F>(
+        &self,
+        env: Environment<H, B>,
+        pass_samples: u32,
+        mut on_pass: F,
+    ) -> Vec<Color>
+    where
+        H: Hittable,
+        B: Background,
+        F: FnMut(&Pass),
     {
+        assert!(pass_samples > 0);
+
         let image_size = self.image_height * self.image_width;
-        let pb = ProgressBar::new(image_size as u64);
+        let passes = self.samples.div_ceil(pass_samples).max(1);
+        let pb = ProgressBar::new(image_size as u64 * passes as u64);
         pb.set_style(
             ProgressStyle::with_template("{spinner} [{elapsed_precise}] [{wide_bar}] {pos}/{len}")
                 .unwrap()
                 .progress_chars("#>-"),
         );
 
-        let colors = (0..image_size)
-            .into_par_iter()
-            .map(|x| (x % self.image_width, x / self.image_width))
-            .map(|(i, j)| {
-                let color = (0..self.samples)
-                    .map(|_| {
-                        let (u, v) = self.uv(i, self.image_height - j);
-                        let r = self.camera.ray(u, v);
-                        Self::ray_color(r, &env, self.depth)
-                    })
-                    .fold(Color::BLACK, |x, y| x + y);
-
-                pb.inc(1);
-
-                (color / self.samples as f64).gamma()
-            })
-            .collect();
+        let empty = Accumulator {
+            color: Color::BLACK,
+            luminance: 0.0,
+            luminance_squared: 0.0,
+            samples: 0,
+            converged: false,
+        };
+        let mut pixels = vec![empty.clone(); image_size];
+        let mut samples = 0;
+        for index in 0..passes {
+            let count = pass_samples.min(self.samples - samples);
+            let results: Vec<Accumulator> = pixels
+                .par_iter()
+                .enumerate()
+                .map(|(x, pixel)| {
+                    let (i, j) = (x % self.image_width, x / self.image_width);
+                    let mut result = empty.clone();
+                    if !pixel.converged {
+                        for _ in 0..count {
+                            let (u, v) = self.uv(i, self.image_height - j);
+                            let r = self.camera.ray(u, v);
+                            let color = Self::ray_color(r, &env, self.depth, None);
+                            let luminance = color.luminance();
+
+                            result.color = result.color + color;
+                            result.luminance += luminance;
+                            result.luminance_squared += luminance * luminance;
+                        }
+                        result.samples = count;
+                    }
+
+                    pb.inc(1);
+
+                    result
+                })
+                .collect();
+
+            samples += count;
+            for (pixel, result) in pixels.iter_mut().zip(results) {
+                pixel.add(result);
+                if let Some(adaptive) = self.adaptive {
+                    pixel.converged |= pixel.samples >= adaptive.min_samples
+                        && pixel.relative_error() <= adaptive.target_error;
+                }
+            }
+
+            let image: Vec<Color> = pixels.iter().map(Accumulator::mean).collect();
+            let sample_counts: Vec<u32> = pixels.iter().map(|pixel| pixel.samples).collect();
+            on_pass(&Pass {
+                index: index as usize,
+                samples,
+                image: &image,
+                sample_counts: &sample_counts,
+            });
+
+            if pixels.iter().all(|pixel| pixel.converged) {
+                break;
+            }
+        }
 
         pb.finish();
-        colors
+        pixels.iter().map(Accumulator::mean).collect()
     }
 
     fn uv(&self, i: usize, j: usize) -> (f64, f64) {
@@ -105,24 +401,188 @@ impl Renderer {
         (u, v)
     }
 
-    fn ray_color<H, B>(r: Ray, env: &Environment<H, B>, depth: u32) -> Color
+    // `bsdf_pdf` is the density with which the previous bounce sampled `r`, or `None` for camera
+    // rays and specular bounces, whose emission is not shared with light sampling.
+    fn ray_color<H, B>(r: Ray, env: &Environment<H, B>, depth: u32, bsdf_pdf: Option<f64>) -> Color
     where
         H: Hittable,
-        B: Fn(&Ray) -> Color + Send + Sync,
+        B: Background,
     {
         if depth == 0 {
             return Color::BLACK;
         }
 
-        if let Some(hit) = env.hit(&r, 1e-6..f64::INFINITY) {
-            let material = hit.material.clone();
-            return material
-                .scatter(&r, hit)
-                .map_or(Color::BLACK, |(color, scattered)| {
-                    color * Self::ray_color(scattered, env, depth - 1)
-                });
+        let hit = match env.hit(&r, 1e-6..f64::INFINITY) {
+            Some(hit) => hit,
+            None => {
+                let background = env.background(&r);
+                return match bsdf_pdf {
+                    Some(pdf) => {
+                        power_heuristic(pdf, env.light_pdf(&r.origin, &r.direction)) * background
+                    }
+                    None => background,
+                };
+            }
+        };
+
+        let material = hit.material.clone();
+        let emitted = material.emitted(&hit);
+        // Emission inside media is only found by scattered rays, so it keeps its full weight.
+        let mut color = match bsdf_pdf {
+            Some(pdf) if !hit.in_medium => {
+                power_heuristic(pdf, env.light_pdf(&r.origin, &r.direction)) * emitted
+            }
+            _ => emitted,
+        };
+
+        if env.has_lights() {
+            color = color + Self::sample_lights(&r, &hit, env);
+        }
+        for light in env.punctual_lights() {
+            color = color + Self::sample_punctual_light(&r, &hit, env, light.as_ref());
+        }
+
+        if let Some(sample) = material.sample(&r, &hit) {
+            let pdf = (!sample.delta).then_some(sample.pdf);
+            let scattered = Ray::new(hit.p, sample.direction).with_time(r.time);
+            color = color + sample.weight * Self::ray_color(scattered, env, depth - 1, pdf);
+        }
+
+        color
+    }
+
+    fn sample_lights<H, B>(r: &Ray, hit: &HitRecord, env: &Environment<H, B>) -> Color
+    where
+        H: Hittable,
+        B: Background,
+    {
+        let direction = env.sample_light(&hit.p);
+        let bsdf_pdf = hit.material.pdf(r, hit, &direction);
+        if bsdf_pdf <= 0.0 {
+            return Color::BLACK;
+        }
+        let f = hit.material.eval(r, hit, &direction);
+
+        let light_pdf = env.light_pdf(&hit.p, &direction);
+        if light_pdf <= 0.0 {
+            return Color::BLACK;
+        }
+
+        // Whatever the shadow ray reaches first is lit, an emitter or the background.
+        let shadow = Ray::new(hit.p.clone(), direction).with_time(r.time);
+        let (emitted, t) = match env.hit_surface(&shadow, 1e-6..f64::INFINITY) {
+            Some(light) => (light.material.emitted(&light), light.t),
+            None => (env.background(&shadow), f64::INFINITY),
+        };
+        let transmittance = env.transmittance(&shadow, 1e-6..t);
+        power_heuristic(light_pdf, bsdf_pdf) / light_pdf * transmittance * f * emitted
+    }
+
+    // Delta lights can't be reached by scattered rays, so each is sampled on every bounce
+    // without multiple importance sampling.
+    fn sample_punctual_light<H, B>(
+        r: &Ray,
+        hit: &HitRecord,
+        env: &Environment<H, B>,
+        light: &dyn Light,
+    ) -> Color
+    where
+        H: Hittable,
+        B: Background,
+    {
+        let sample = match light.sample(&hit.p) {
+            Some(sample) => sample,
+            None => return Color::BLACK,
+        };
+
+        let f = hit.material.eval(r, hit, &sample.direction);
+        if f.luminance() <= 0.0 {
+            return Color::BLACK;
+        }
+
+        let shadow = Ray::new(hit.p.clone(), sample.direction).with_time(r.time);
+        let t_range = 1e-6..sample.distance;
+        if env.hit_surface(&shadow, t_range.clone()).is_some() {
+            return Color::BLACK;
+        }
+        env.transmittance(&shadow, t_range) * f * sample.irradiance
+    }
+}
+
+fn power_heuristic(pdf: f64, other: f64) -> f64 {
+    if other <= 0.0 {
+        return 1.0;
+    }
+
+    let (a, b) = (pdf * pdf, other * other);
+    a / (a + b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::{ConstantMedium, HittableList, Sphere};
+    use crate::material::{BsdfSample, DiffuseLight, Material};
+    use crate::vector3::Vector3;
+    use std::sync::Arc;
+
+    // A medium that glows white and absorbs everything.
+    struct Glow;
+
+    impl Material for Glow {
+        fn sample(&self, _: &Ray, _: &HitRecord) -> Option<BsdfSample> {
+            None
+        }
+
+        fn emitted(&self, _: &HitRecord) -> Color {
+            Color::WHITE
+        }
+    }
+
+    fn glow_seen_through(light: bool) -> Color {
+        let medium = ConstantMedium::new(
+            Sphere::new(Vector3::new(0.0, 0.0, 0.0), 1.0, Arc::new(Glow)),
+            1e9,
+            Arc::new(Glow),
+        );
+        let lamp: Arc<dyn Hittable> = Arc::new(Sphere::new(
+            Vector3::new(0.0, 0.0, -5.0),
+            1.0,
+            Arc::new(DiffuseLight::new(Color::BLACK)),
+        ));
+
+        let mut env = Environment::new(
+            HittableList::from_vec(vec![Box::new(medium), Box::new(lamp.clone())]),
+            |_: &Ray| Color::BLACK,
+        );
+        if light {
+            env = env.with_lights(vec![lamp]);
+        }
+
+        // As if scattered towards the medium by a previous bounce.
+        let r = Ray::new(Vector3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0));
+        Renderer::ray_color(r, &env, 2, Some(0.1))
+    }
+
+    #[test]
+    fn zero_power_light_leaves_medium_emission_unchanged() {
+        for light in [false, true] {
+            let c = glow_seen_through(light);
+            assert!((c.r - 1.0).abs() < 1e-9, "light: {light}, color: {c:?}");
         }
+    }
 
-        env.background(&r)
+    #[test]
+    fn linear_images_skip_srgb_encoding() {
+        let path = std::env::temp_dir().join(format!("lumo-linear-{}.png", std::process::id()));
+        let gray = |image: Image| {
+            image.save(&path).unwrap();
+            let value = image::open(&path).unwrap().to_luma8().get_pixel(0, 0)[0];
+            std::fs::remove_file(&path).unwrap();
+            value
+        };
+        let half = || Image::new(1, 1, vec![Color::new(0.5, 0.5, 0.5)]);
+        assert_eq!(gray(half().linear()), 128);
+        assert_eq!(gray(half()), 188);
     }
 }
@@ -0,0 +1,401 @@
+use crate::aabb::Aabb;
+use crate::vector3::Vector3;
+use std::sync::Arc;
+
+// A signed distance bound: negative inside, and never more than the distance to the surface,
+// so a ray may safely advance by it.
+pub trait Sdf: Send + Sync {
+    fn distance(&self, p: &Vector3) -> f64;
+
+    // Bounds of the interior, if finite.
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
+
+    fn union<S: Sdf>(self, other: S) -> Union<Self, S>
+    where
+        Self: Sized,
+    {
+        Union { a: self, b: other }
+    }
+
+    // Blends both shapes together over a distance of about `k`.
+    fn smooth_union<S: Sdf>(self, other: S, k: f64) -> SmoothUnion<Self, S>
+    where
+        Self: Sized,
+    {
+        SmoothUnion {
+            a: self,
+            b: other,
+            k,
+        }
+    }
+
+    fn intersection<S: Sdf>(self, other: S) -> Intersection<Self, S>
+    where
+        Self: Sized,
+    {
+        Intersection { a: self, b: other }
+    }
+
+    fn difference<S: Sdf>(self, other: S) -> Difference<Self, S>
+    where
+        Self: Sized,
+    {
+        Difference { a: self, b: other }
+    }
+
+    fn translate(self, offset: Vector3) -> Translate<Self>
+    where
+        Self: Sized,
+    {
+        Translate {
+            inner: self,
+            offset,
+        }
+    }
+
+    fn scale(self, factor: f64) -> Scale<Self>
+    where
+        Self: Sized,
+    {
+        Scale {
+            inner: self,
+            factor,
+        }
+    }
+
+    // Rotates each slice around the y axis by `rate` radians per unit height. This stretches
+    // distances, so the marching step should be scaled down for strong twists.
+    fn twist(self, rate: f64) -> Twist<Self>
+    where
+        Self: Sized,
+    {
+        Twist { inner: self, rate }
+    }
+
+    // Repeats the shape endlessly on a grid with the given cell size, centered on the origin.
+    // The shape should fit within one cell; a zero period leaves that axis alone.
+    fn repeat(self, period: Vector3) -> Repeat<Self>
+    where
+        Self: Sized,
+    {
+        Repeat {
+            inner: self,
+            period,
+        }
+    }
+}
+
+impl<F: Fn(&Vector3) -> f64 + Send + Sync> Sdf for F {
+    fn distance(&self, p: &Vector3) -> f64 {
+        self(p)
+    }
+}
+
+impl<T: Sdf + ?Sized> Sdf for Arc<T> {
+    fn distance(&self, p: &Vector3) -> f64 {
+        (**self).distance(p)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        (**self).bounding_box()
+    }
+}
+
+fn symmetric(e: &Vector3) -> Aabb {
+    Aabb::new(-e, e.clone())
+}
+
+pub struct Sphere {
+    radius: f64,
+}
+
+impl Sphere {
+    pub fn new(radius: f64) -> Self {
+        Self { radius }
+    }
+}
+
+impl Sdf for Sphere {
+    fn distance(&self, p: &Vector3) -> f64 {
+        p.norm() - self.radius
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let r = self.radius;
+        Some(symmetric(&Vector3::new(r, r, r)))
+    }
+}
+
+// A box with half extents `half`, its edges rounded off by `radius`.
+pub struct RoundedBox {
+    half: Vector3,
+    radius: f64,
+}
+
+impl RoundedBox {
+    pub fn new(half: Vector3, radius: f64) -> Self {
+        Self { half, radius }
+    }
+}
+
+impl Sdf for RoundedBox {
+    fn distance(&self, p: &Vector3) -> f64 {
+        let r = self.radius;
+        let q = Vector3::new(
+            p.x.abs() - self.half.x + r,
+            p.y.abs() - self.half.y + r,
+            p.z.abs() - self.half.z + r,
+        );
+        let outside = q.max(&Vector3::ZERO).norm();
+        let inside = q.x.max(q.y).max(q.z).min(0.0);
+        outside + inside - r
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(symmetric(&self.half))
+    }
+}
+
+// A ring in the xz plane.
+pub struct Torus {
+    major: f64,
+    minor: f64,
+}
+
+impl Torus {
+    pub fn new(major: f64, minor: f64) -> Self {
+        Self { major, minor }
+    }
+}
+
+impl Sdf for Torus {
+    fn distance(&self, p: &Vector3) -> f64 {
+        (p.x.hypot(p.z) - self.major).hypot(p.y) - self.minor
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let r = self.major + self.minor;
+        Some(symmetric(&Vector3::new(r, self.minor, r)))
+    }
+}
+
+// The Mandelbulb fractal, by its usual distance estimate.
+pub struct Mandelbulb {
+    power: f64,
+    iterations: usize,
+}
+
+impl Mandelbulb {
+    pub fn new(power: f64) -> Self {
+        Self {
+            power,
+            iterations: 12,
+        }
+    }
+
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+}
+
+impl Sdf for Mandelbulb {
+    fn distance(&self, p: &Vector3) -> f64 {
+        let n = self.power;
+        let mut z = p.clone();
+        let mut dr = 1.0;
+        let mut r = z.norm();
+
+        for _ in 0..self.iterations {
+            if r > 2.0 {
+                break;
+            }
+
+            let theta = (z.z / r).clamp(-1.0, 1.0).acos() * n;
+            let phi = z.y.atan2(z.x) * n;
+            dr = n * r.powf(n - 1.0) * dr + 1.0;
+
+            let zr = r.powf(n);
+            z =
+                zr * Vector3::new(
+                    theta.sin() * phi.cos(),
+                    theta.sin() * phi.sin(),
+                    theta.cos(),
+                ) + p;
+            r = z.norm();
+        }
+
+        if r == 0.0 {
+            return 0.0;
+        }
+        0.5 * r.ln() * r / dr
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(symmetric(&Vector3::new(1.5, 1.5, 1.5)))
+    }
+}
+
+fn union_bbox(a: Option<Aabb>, b: Option<Aabb>) -> Option<Aabb> {
+    Some(a?.union(&b?))
+}
+
+pub struct Union<A: Sdf, B: Sdf> {
+    a: A,
+    b: B,
+}
+
+impl<A: Sdf, B: Sdf> Sdf for Union<A, B> {
+    fn distance(&self, p: &Vector3) -> f64 {
+        self.a.distance(p).min(self.b.distance(p))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        union_bbox(self.a.bounding_box(), self.b.bounding_box())
+    }
+}
+
+pub struct SmoothUnion<A: Sdf, B: Sdf> {
+    a: A,
+    b: B,
+    k: f64,
+}
+
+impl<A: Sdf, B: Sdf> Sdf for SmoothUnion<A, B> {
+    // Polynomial smooth minimum; it stays within k / 4 below the plain minimum.
+    fn distance(&self, p: &Vector3) -> f64 {
+        let (a, b) = (self.a.distance(p), self.b.distance(p));
+        if self.k <= 0.0 {
+            return a.min(b);
+        }
+
+        let h = (self.k - (a - b).abs()).max(0.0) / self.k;
+        a.min(b) - 0.25 * h * h * self.k
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let bbox = union_bbox(self.a.bounding_box(), self.b.bounding_box())?;
+        let pad = 0.25 * self.k.max(0.0);
+        let pad = Vector3::new(pad, pad, pad);
+        Some(Aabb::new(&bbox.min - &pad, &bbox.max + &pad))
+    }
+}
+
+pub struct Intersection<A: Sdf, B: Sdf> {
+    a: A,
+    b: B,
+}
+
+impl<A: Sdf, B: Sdf> Sdf for Intersection<A, B> {
+    fn distance(&self, p: &Vector3) -> f64 {
+        self.a.distance(p).max(self.b.distance(p))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        match (self.a.bounding_box(), self.b.bounding_box()) {
+            (Some(a), Some(b)) => Some(a.intersection(&b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+pub struct Difference<A: Sdf, B: Sdf> {
+    a: A,
+    b: B,
+}
+
+impl<A: Sdf, B: Sdf> Sdf for Difference<A, B> {
+    fn distance(&self, p: &Vector3) -> f64 {
+        self.a.distance(p).max(-self.b.distance(p))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.a.bounding_box()
+    }
+}
+
+pub struct Translate<S: Sdf> {
+    inner: S,
+    offset: Vector3,
+}
+
+impl<S: Sdf> Sdf for Translate<S> {
+    fn distance(&self, p: &Vector3) -> f64 {
+        self.inner.distance(&(p - &self.offset))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let bbox = self.inner.bounding_box()?;
+        Some(Aabb::new(
+            &bbox.min + &self.offset,
+            &bbox.max + &self.offset,
+        ))
+    }
+}
+
+pub struct Scale<S: Sdf> {
+    inner: S,
+    factor: f64,
+}
+
+impl<S: Sdf> Sdf for Scale<S> {
+    fn distance(&self, p: &Vector3) -> f64 {
+        self.inner.distance(&(p / self.factor)) * self.factor
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let bbox = self.inner.bounding_box()?;
+        let (a, b) = (self.factor * &bbox.min, self.factor * &bbox.max);
+        Some(Aabb::new(a.min(&b), a.max(&b)))
+    }
+}
+
+pub struct Twist<S: Sdf> {
+    inner: S,
+    rate: f64,
+}
+
+impl<S: Sdf> Sdf for Twist<S> {
+    fn distance(&self, p: &Vector3) -> f64 {
+        let (sin, cos) = (self.rate * p.y).sin_cos();
+        let q = Vector3::new(cos * p.x - sin * p.z, p.y, sin * p.x + cos * p.z);
+        self.inner.distance(&q)
+    }
+
+    // Any rotation about y keeps within the circle through the farthest corner.
+    fn bounding_box(&self) -> Option<Aabb> {
+        let bbox = self.inner.bounding_box()?;
+        let x = bbox.min.x.abs().max(bbox.max.x.abs());
+        let z = bbox.min.z.abs().max(bbox.max.z.abs());
+        let r = x.hypot(z);
+        Some(Aabb::new(
+            Vector3::new(-r, bbox.min.y, -r),
+            Vector3::new(r, bbox.max.y, r),
+        ))
+    }
+}
+
+pub struct Repeat<S: Sdf> {
+    inner: S,
+    period: Vector3,
+}
+
+impl<S: Sdf> Sdf for Repeat<S> {
+    fn distance(&self, p: &Vector3) -> f64 {
+        let wrap = |x: f64, c: f64| {
+            if c > 0.0 {
+                x - c * (x / c).round()
+            } else {
+                x
+            }
+        };
+        let q = Vector3::new(
+            wrap(p.x, self.period.x),
+            wrap(p.y, self.period.y),
+            wrap(p.z, self.period.z),
+        );
+        self.inner.distance(&q)
+    }
+}
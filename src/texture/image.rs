@@ -0,0 +1,113 @@
+use super::Texture;
+use crate::color::Color;
+use crate::vector3::Vector3;
+use image::DynamicImage;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapMode {
+    Repeat,
+    Mirror,
+    Clamp,
+}
+
+impl WrapMode {
+    fn apply(self, i: i64, n: usize) -> usize {
+        let n = n as i64;
+        let i = match self {
+            Self::Repeat => i.rem_euclid(n),
+            Self::Mirror => {
+                let i = i.rem_euclid(2 * n);
+                if i < n {
+                    i
+                } else {
+                    2 * n - 1 - i
+                }
+            }
+            Self::Clamp => i.clamp(0, n - 1),
+        };
+        i as usize
+    }
+}
+
+pub struct ImageTexture {
+    width: usize,
+    height: usize,
+    texels: Vec<Color>,
+    wrap: WrapMode,
+}
+
+impl ImageTexture {
+    pub fn new(width: usize, height: usize, texels: Vec<Color>) -> Self {
+        assert_eq!(texels.len(), width * height);
+        Self {
+            width,
+            height,
+            texels,
+            wrap: WrapMode::Repeat,
+        }
+    }
+
+    pub fn open<P: AsRef<Path>>(path: P) -> image::ImageResult<Self> {
+        Ok(Self::from_image(image::open(path)?))
+    }
+
+    // For data such as roughness or metallic maps, whose 8-bit values are not sRGB encoded.
+    pub fn open_linear<P: AsRef<Path>>(path: P) -> image::ImageResult<Self> {
+        Ok(Self::from_linear_image(image::open(path)?))
+    }
+
+    pub fn from_image(img: DynamicImage) -> Self {
+        let linear = matches!(
+            img,
+            DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+        );
+        Self::decode(img, !linear)
+    }
+
+    pub fn from_linear_image(img: DynamicImage) -> Self {
+        Self::decode(img, false)
+    }
+
+    fn decode(img: DynamicImage, srgb: bool) -> Self {
+        let img = img.into_rgb32f();
+        let texels = img
+            .pixels()
+            .map(|p| {
+                let c = Color::new(p[0] as f64, p[1] as f64, p[2] as f64);
+                if srgb {
+                    c.srgb_to_linear()
+                } else {
+                    c
+                }
+            })
+            .collect();
+
+        Self::new(img.width() as usize, img.height() as usize, texels)
+    }
+
+    pub fn with_wrap(mut self, wrap: WrapMode) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    fn texel(&self, i: i64, j: i64) -> &Color {
+        let i = self.wrap.apply(i, self.width);
+        let j = self.wrap.apply(j, self.height);
+        &self.texels[j * self.width + i]
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _: &Vector3) -> Color {
+        let x = u * self.width as f64 - 0.5;
+        let y = (1.0 - v) * self.height as f64 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (i, j) = (x0 as i64, y0 as i64);
+
+        let top = self.texel(i, j).lerp(self.texel(i + 1, j), tx);
+        let bottom = self.texel(i, j + 1).lerp(self.texel(i + 1, j + 1), tx);
+        top.lerp(&bottom, ty)
+    }
+}
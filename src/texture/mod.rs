@@ -0,0 +1,18 @@
+use crate::color::Color;
+use crate::vector3::Vector3;
+
+pub trait Texture: Send + Sync {
+    fn value(&self, u: f64, v: f64, p: &Vector3) -> Color;
+}
+
+mod checker;
+mod image;
+mod noise;
+mod perlin;
+mod solid;
+
+pub use self::image::*;
+pub use checker::*;
+pub use noise::*;
+pub use perlin::*;
+pub use solid::*;
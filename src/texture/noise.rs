@@ -0,0 +1,59 @@
+use super::{Perlin, Texture};
+use crate::color::Color;
+use crate::vector3::Vector3;
+
+enum NoiseKind {
+    Perlin,
+    Turbulence(u32),
+    Marble(u32),
+}
+
+pub struct NoiseTexture {
+    perlin: Perlin,
+    scale: f64,
+    color: Color,
+    kind: NoiseKind,
+}
+
+impl NoiseTexture {
+    pub fn perlin(scale: f64) -> Self {
+        Self::with_kind(scale, NoiseKind::Perlin)
+    }
+
+    pub fn turbulence(scale: f64, depth: u32) -> Self {
+        Self::with_kind(scale, NoiseKind::Turbulence(depth))
+    }
+
+    pub fn marble(scale: f64, depth: u32) -> Self {
+        Self::with_kind(scale, NoiseKind::Marble(depth))
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    fn with_kind(scale: f64, kind: NoiseKind) -> Self {
+        Self {
+            perlin: Perlin::new(),
+            scale,
+            color: Color::WHITE,
+            kind,
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _: f64, _: f64, p: &Vector3) -> Color {
+        let q = self.scale * p;
+        let t = match self.kind {
+            NoiseKind::Perlin => 0.5 * (1.0 + self.perlin.noise(&q)),
+            NoiseKind::Turbulence(depth) => self.perlin.turbulence(&q, depth),
+            NoiseKind::Marble(depth) => {
+                0.5 * (1.0 + (q.z + 10.0 * self.perlin.turbulence(p, depth)).sin())
+            }
+        };
+
+        t * &self.color
+    }
+}
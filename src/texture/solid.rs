@@ -0,0 +1,25 @@
+use super::Texture;
+use crate::color::Color;
+use crate::vector3::Vector3;
+
+pub struct SolidColor {
+    color: Color,
+}
+
+impl SolidColor {
+    pub fn new(color: Color) -> Self {
+        Self { color }
+    }
+}
+
+impl From<Color> for SolidColor {
+    fn from(color: Color) -> Self {
+        Self::new(color)
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _: f64, _: f64, _: &Vector3) -> Color {
+        self.color.clone()
+    }
+}
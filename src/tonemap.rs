@@ -0,0 +1,98 @@
+use crate::color::Color;
+
+type Matrix3 = [[f64; 3]; 3];
+
+fn mul(m: &Matrix3, c: &Color) -> Color {
+    let row = |r: &[f64; 3]| r[0] * c.r + r[1] * c.g + r[2] * c.b;
+    Color::new(row(&m[0]), row(&m[1]), row(&m[2]))
+}
+
+fn map(c: Color, f: impl Fn(f64) -> f64) -> Color {
+    Color::new(f(c.r), f(c.g), f(c.b))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ToneMap {
+    #[default]
+    Clamp,
+    Reinhard,
+    ExtendedReinhard {
+        white: f64,
+    },
+    Aces,
+    Agx,
+}
+
+impl ToneMap {
+    pub fn apply(self, c: Color) -> Color {
+        let c = match self {
+            Self::Clamp => c,
+            Self::Reinhard => scale_luminance(c, |l| l / (1.0 + l)),
+            Self::ExtendedReinhard { white } => {
+                scale_luminance(c, |l| l * (1.0 + l / (white * white)) / (1.0 + l))
+            }
+            Self::Aces => aces(c),
+            Self::Agx => agx(c),
+        };
+
+        map(c, |v| v.clamp(0.0, 1.0))
+    }
+}
+
+fn scale_luminance(c: Color, f: impl Fn(f64) -> f64) -> Color {
+    let l = c.luminance();
+    if l <= 0.0 {
+        Color::BLACK
+    } else {
+        f(l) / l * c
+    }
+}
+
+// Stephen Hill's fit of the ACES RRT and sRGB ODT.
+fn aces(c: Color) -> Color {
+    const INPUT: Matrix3 = [
+        [0.59719, 0.35458, 0.04823],
+        [0.07600, 0.90834, 0.01566],
+        [0.02840, 0.13383, 0.83777],
+    ];
+    const OUTPUT: Matrix3 = [
+        [1.60475, -0.53108, -0.07367],
+        [-0.10208, 1.10813, -0.00605],
+        [-0.00327, -0.07276, 1.07602],
+    ];
+
+    let c = map(mul(&INPUT, &c), |v| {
+        let a = v * (v + 0.0245786) - 0.000090537;
+        let b = v * (0.983729 * v + 0.4329510) + 0.238081;
+        a / b
+    });
+
+    mul(&OUTPUT, &c)
+}
+
+// Minimal AgX with the default contrast look, after Benjamin Wrensch's approximation.
+fn agx(c: Color) -> Color {
+    const INSET: Matrix3 = [
+        [0.842479062253094, 0.0784335999999992, 0.0792237451477643],
+        [0.0423282422610123, 0.878468636469772, 0.0791661274605434],
+        [0.0423756549057051, 0.0784336, 0.879142973793104],
+    ];
+    const OUTSET: Matrix3 = [
+        [1.19687900512017, -0.0980208811401368, -0.0990297440797205],
+        [-0.0528968517574562, 1.15190312990417, -0.0989611768448433],
+        [-0.0529716355144438, -0.0980434501171241, 1.15107367264116],
+    ];
+    const MIN_EV: f64 = -12.47393;
+    const MAX_EV: f64 = 4.026069;
+
+    let c = map(mul(&INSET, &c), |v| {
+        let x = (v.max(1e-10).log2().clamp(MIN_EV, MAX_EV) - MIN_EV) / (MAX_EV - MIN_EV);
+        let x2 = x * x;
+        let x4 = x2 * x2;
+
+        15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x
+            - 0.00232
+    });
+
+    map(mul(&OUTSET, &c), |v| v.max(0.0).powf(2.2))
+}
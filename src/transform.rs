@@ -0,0 +1,259 @@
+use crate::aabb::Aabb;
+use crate::matrix4::Matrix4;
+use crate::quaternion::Quaternion;
+use crate::ray::Ray;
+use crate::vector3::Vector3;
+
+// An invertible affine transform, keeping its inverse alongside.
+#[derive(Debug, Clone)]
+pub struct Transform {
+    matrix: Matrix4,
+    inverse: Matrix4,
+}
+
+impl Transform {
+    pub fn new(matrix: Matrix4) -> Option<Self> {
+        let inverse = matrix.inverse()?;
+        Some(Self { matrix, inverse })
+    }
+
+    pub fn identity() -> Self {
+        Self {
+            matrix: Matrix4::IDENTITY,
+            inverse: Matrix4::IDENTITY,
+        }
+    }
+
+    pub fn translate(t: &Vector3) -> Self {
+        Self {
+            matrix: Matrix4::translation(t),
+            inverse: Matrix4::translation(&-t),
+        }
+    }
+
+    pub fn scale(s: &Vector3) -> Self {
+        assert!(s.x != 0.0 && s.y != 0.0 && s.z != 0.0);
+        Self {
+            matrix: Matrix4::scaling(s),
+            inverse: Matrix4::scaling(&Vector3::new(1.0 / s.x, 1.0 / s.y, 1.0 / s.z)),
+        }
+    }
+
+    pub fn rotate(axis: &Vector3, angle: f64) -> Self {
+        let matrix = Matrix4::rotation(axis, angle);
+        Self {
+            inverse: matrix.transpose(),
+            matrix,
+        }
+    }
+
+    // Rigid placement taking the local y axis to `up` and the local origin to `origin`.
+    pub fn orient(origin: &Vector3, up: &Vector3) -> Self {
+        let y = up.normalized();
+        let (z, x) = y.orthonormal_basis();
+        let matrix = Matrix4::new([
+            [x.x, y.x, z.x, 0.0],
+            [x.y, y.y, z.y, 0.0],
+            [x.z, y.z, z.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        let rotation = Self {
+            inverse: matrix.transpose(),
+            matrix,
+        };
+
+        rotation.then(&Self::translate(origin))
+    }
+
+    // Applies `self` first, then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        Self {
+            matrix: &next.matrix * &self.matrix,
+            inverse: &self.inverse * &next.inverse,
+        }
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self {
+            matrix: self.inverse.clone(),
+            inverse: self.matrix.clone(),
+        }
+    }
+
+    pub fn matrix(&self) -> &Matrix4 {
+        &self.matrix
+    }
+
+    pub fn point(&self, p: &Vector3) -> Vector3 {
+        self.matrix.transform_point(p)
+    }
+
+    pub fn vector(&self, v: &Vector3) -> Vector3 {
+        self.matrix.transform_vector(v)
+    }
+
+    // Normals transform by the inverse transpose; the result is not normalized.
+    pub fn normal(&self, n: &Vector3) -> Vector3 {
+        self.inverse.transpose().transform_vector(n)
+    }
+
+    // Keeps the ray parameter: `t` on the transformed ray is the same point as on `r`.
+    pub fn ray(&self, r: &Ray) -> Ray {
+        Ray::new(self.point(&r.origin), self.vector(&r.direction)).with_time(r.time)
+    }
+
+    pub fn bbox(&self, b: &Aabb) -> Aabb {
+        let corners = (0..8).map(|i| {
+            self.point(&Vector3::new(
+                if i & 1 == 0 { b.min.x } else { b.max.x },
+                if i & 2 == 0 { b.min.y } else { b.max.y },
+                if i & 4 == 0 { b.min.z } else { b.max.z },
+            ))
+        });
+        let corners: Vec<Vector3> = corners.collect();
+        Aabb::from_points(&corners).unwrap()
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+// A pose at a point in time: scale, then rotate, then translate.
+#[derive(Debug, Clone)]
+pub struct Keyframe {
+    time: f64,
+    translation: Vector3,
+    rotation: Quaternion,
+    scale: Vector3,
+}
+
+impl Keyframe {
+    pub fn new(time: f64) -> Self {
+        Self {
+            time,
+            translation: Vector3::ZERO,
+            rotation: Quaternion::IDENTITY,
+            scale: Vector3::new(1.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn with_translation(mut self, translation: Vector3) -> Self {
+        self.translation = translation;
+        self
+    }
+
+    pub fn with_rotation(mut self, axis: &Vector3, angle: f64) -> Self {
+        self.rotation = Quaternion::from_axis_angle(axis, angle);
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vector3) -> Self {
+        assert!(scale.x != 0.0 && scale.y != 0.0 && scale.z != 0.0);
+        self.scale = scale;
+        self
+    }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn transform(&self) -> Transform {
+        let rotation = self.rotation.to_matrix();
+        let rotation = Transform {
+            inverse: rotation.transpose(),
+            matrix: rotation,
+        };
+
+        Transform::scale(&self.scale)
+            .then(&rotation)
+            .then(&Transform::translate(&self.translation))
+    }
+
+    fn lerp(&self, next: &Self, s: f64) -> Self {
+        Self {
+            time: self.time + s * (next.time - self.time),
+            translation: &self.translation + s * (&next.translation - &self.translation),
+            rotation: self.rotation.slerp(&next.rotation, s),
+            scale: &self.scale + s * (&next.scale - &self.scale),
+        }
+    }
+}
+
+// Keyframes sorted by time. Poses are interpolated in between, translation and scale
+// linearly and rotation along the shortest arc, and held before the first and after the
+// last keyframe. Each scale axis must keep its sign, or some pose would be singular.
+#[derive(Debug, Clone)]
+pub struct Animation {
+    keyframes: Vec<Keyframe>,
+}
+
+impl Animation {
+    pub fn new(mut keyframes: Vec<Keyframe>) -> Self {
+        assert!(Self::is_valid(&keyframes));
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self { keyframes }
+    }
+
+    pub fn is_valid(keyframes: &[Keyframe]) -> bool {
+        let Some(first) = keyframes.first() else {
+            return false;
+        };
+
+        let sign = |k: &Keyframe| {
+            let s = &k.scale;
+            [s.x > 0.0, s.y > 0.0, s.z > 0.0]
+        };
+        keyframes.iter().all(|k| sign(k) == sign(first))
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    pub fn pose(&self, time: f64) -> Keyframe {
+        let i = self.keyframes.partition_point(|k| k.time <= time);
+        if i == 0 {
+            return self.keyframes[0].clone();
+        }
+        if i == self.keyframes.len() {
+            return self.keyframes[i - 1].clone();
+        }
+
+        let (a, b) = (&self.keyframes[i - 1], &self.keyframes[i]);
+        a.lerp(b, (time - a.time) / (b.time - a.time))
+    }
+
+    pub fn at(&self, time: f64) -> Transform {
+        self.pose(time).transform()
+    }
+
+    // Bounds `b` over the whole animation. Poses are sampled along each segment; between
+    // samples a point moves at most half a step times its speed, which pads the result.
+    pub fn bbox(&self, b: &Aabb) -> Aabb {
+        const STEPS: usize = 32;
+
+        let first = self.keyframes[0].transform().bbox(b);
+        let reach = b.max.max(&-&b.min).norm();
+
+        self.keyframes.windows(2).fold(first, |acc, pair| {
+            let (k0, k1) = (&pair[0], &pair[1]);
+
+            let scale = k0.scale.max(&k1.scale).max(&-k0.scale.min(&k1.scale));
+            let scale = scale.x.max(scale.y).max(scale.z);
+            let speed = (&k1.translation - &k0.translation).norm()
+                + k0.rotation.angle(&k1.rotation) * scale * reach
+                + (&k1.scale - &k0.scale).norm() * reach;
+            let pad = 0.5 * speed / STEPS as f64;
+            let pad = Vector3::new(pad, pad, pad);
+
+            (0..=STEPS).fold(acc, |acc, i| {
+                let pose = k0.lerp(k1, i as f64 / STEPS as f64);
+                let bbox = pose.transform().bbox(b);
+                acc.union(&Aabb::new(&bbox.min - &pad, &bbox.max + &pad))
+            })
+        })
+    }
+}
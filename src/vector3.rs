@@ -42,6 +42,38 @@ impl Vector3 {
     pub fn is_nearly_zero(&self) -> bool {
         self.x.abs() < 1e-8 && self.y.abs() < 1e-8 && self.z.abs() < 1e-8
     }
+
+    pub fn orthonormal_basis(&self) -> (Self, Self) {
+        let sign = 1f64.copysign(self.z);
+        let a = -1.0 / (sign + self.z);
+        let b = self.x * self.y * a;
+
+        (
+            Self::new(1.0 + sign * self.x * self.x * a, sign * b, -sign * self.x),
+            Self::new(b, sign + self.y * self.y * a, -self.y),
+        )
+    }
+
+    pub fn min(&self, v: &Self) -> Self {
+        Self::new(self.x.min(v.x), self.y.min(v.y), self.z.min(v.z))
+    }
+
+    pub fn max(&self, v: &Self) -> Self {
+        Self::new(self.x.max(v.x), self.y.max(v.y), self.z.max(v.z))
+    }
+}
+
+impl ops::Index<usize> for Vector3 {
+    type Output = f64;
+
+    fn index(&self, axis: usize) -> &f64 {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("axis out of range: {}", axis),
+        }
+    }
 }
 
 overload!((u: ?Vector3) + (v: ?Vector3) -> Vector3 {